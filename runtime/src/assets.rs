@@ -0,0 +1,274 @@
+use support::{StorageValue, StorageMap, dispatch::Result, decl_module, decl_storage, decl_event, ensure};
+use runtime_primitives::traits::{As, CheckedSub, CheckedAdd};
+use system::ensure_signed;
+
+/// Identifier of an asset. `0` is reserved for the native `balances` currency.
+pub type AssetId = u32;
+
+pub const NATIVE_ASSET_ID: AssetId = 0;
+
+pub trait Trait: balances::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+}
+
+decl_event! (
+    pub enum Event<T>
+    where AccountId = <T as system::Trait>::AccountId,
+    Balance = <T as balances::Trait>::Balance,
+    {
+        Issued(AssetId, AccountId, Balance),
+        Transferred(AssetId, AccountId, AccountId, Balance),
+        Reserved(AssetId, AccountId, Balance),
+        Unreserved(AssetId, AccountId, Balance),
+    }
+);
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Assets {
+        AssetsCount get(assets_count): AssetId;
+        Owner get(owner): map AssetId => Option<T::AccountId>;
+        TotalSupply get(total_supply): map AssetId => T::Balance;
+        FreeBalance get(free_balance): map (AssetId, T::AccountId) => T::Balance;
+        ReservedBalance get(reserved_balance): map (AssetId, T::AccountId) => T::Balance;
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event<T>() = default;
+
+        pub fn issue(origin, total: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(total > <T::Balance as As<u64>>::sa(0), "Total supply must be more than 0");
+            let asset_id = Self::assets_count().checked_add(1).ok_or("overflow in calculating asset id")?;
+
+            <Owner<T>>::insert(asset_id, sender.clone());
+            <TotalSupply<T>>::insert(asset_id, total);
+            <FreeBalance<T>>::insert((asset_id, sender.clone()), total);
+            <AssetsCount<T>>::put(asset_id);
+
+            Self::deposit_event(RawEvent::Issued(asset_id, sender, total));
+            Ok(())
+        }
+
+        pub fn transfer(origin, asset_id: AssetId, dest: T::AccountId, value: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(Self::is_issued(asset_id), "Asset is not issued");
+            ensure!(sender != dest, "Cannot transfer to self");
+
+            let new_sender_balance = Self::free_balance((asset_id, sender.clone())).checked_sub(&value).ok_or("Balance is not enough")?;
+            let new_dest_balance = Self::free_balance((asset_id, dest.clone())).checked_add(&value).ok_or("overflow in calculating balance")?;
+
+            <FreeBalance<T>>::insert((asset_id, sender.clone()), new_sender_balance);
+            <FreeBalance<T>>::insert((asset_id, dest.clone()), new_dest_balance);
+
+            Self::deposit_event(RawEvent::Transferred(asset_id, sender, dest, value));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    pub fn is_issued(asset_id: AssetId) -> bool {
+        asset_id != NATIVE_ASSET_ID && asset_id <= Self::assets_count()
+    }
+
+    /// Take `value` of `asset_id` from `who`. Nothing is written if the balance is not enough.
+    pub fn withdraw(asset_id: AssetId, who: &T::AccountId, value: T::Balance) -> Result {
+        ensure!(Self::is_issued(asset_id), "Asset is not issued");
+
+        let new_balance = Self::free_balance((asset_id, who.clone())).checked_sub(&value).ok_or("Balance is not enough")?;
+        <FreeBalance<T>>::insert((asset_id, who.clone()), new_balance);
+        Ok(())
+    }
+
+    /// Give `value` of `asset_id` to `who`. The total supply is unchanged, so this must only
+    /// return funds that were previously taken by `withdraw`.
+    pub fn deposit(asset_id: AssetId, who: &T::AccountId, value: T::Balance) -> Result {
+        ensure!(Self::is_issued(asset_id), "Asset is not issued");
+
+        let new_balance = Self::free_balance((asset_id, who.clone())).checked_add(&value).ok_or("overflow in calculating balance")?;
+        <FreeBalance<T>>::insert((asset_id, who.clone()), new_balance);
+        Ok(())
+    }
+
+    /// Move `value` of `asset_id` from the free to the reserved balance of `who`.
+    pub fn reserve(asset_id: AssetId, who: &T::AccountId, value: T::Balance) -> Result {
+        ensure!(Self::is_issued(asset_id), "Asset is not issued");
+
+        let new_free_balance = Self::free_balance((asset_id, who.clone())).checked_sub(&value).ok_or("Balance is not enough")?;
+        let new_reserved_balance = Self::reserved_balance((asset_id, who.clone())).checked_add(&value).ok_or("overflow in calculating balance")?;
+        <FreeBalance<T>>::insert((asset_id, who.clone()), new_free_balance);
+        <ReservedBalance<T>>::insert((asset_id, who.clone()), new_reserved_balance);
+
+        Self::deposit_event(RawEvent::Reserved(asset_id, who.clone(), value));
+        Ok(())
+    }
+
+    /// Move up to `value` of `asset_id` from the reserved back to the free balance of `who`.
+    pub fn unreserve(asset_id: AssetId, who: &T::AccountId, value: T::Balance) {
+        let reserved_balance = Self::reserved_balance((asset_id, who.clone()));
+        let actual = if value < reserved_balance { value } else { reserved_balance };
+        if actual == <T::Balance as As<u64>>::sa(0) {
+            return;
+        }
+
+        <ReservedBalance<T>>::insert((asset_id, who.clone()), reserved_balance - actual);
+        <FreeBalance<T>>::mutate((asset_id, who.clone()), |n| *n += actual);
+
+        Self::deposit_event(RawEvent::Unreserved(asset_id, who.clone(), actual));
+    }
+
+    /// Take up to `value` of `asset_id` out of the reserved balance of `who`. Like `withdraw`,
+    /// this leaves the total supply unchanged, as the caller holds what was taken and gives it
+    /// back with `deposit`.
+    pub fn slash_reserved(asset_id: AssetId, who: &T::AccountId, value: T::Balance) {
+        let reserved_balance = Self::reserved_balance((asset_id, who.clone()));
+        let actual = if value < reserved_balance { value } else { reserved_balance };
+        <ReservedBalance<T>>::insert((asset_id, who.clone()), reserved_balance - actual);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use runtime_io::with_externalities;
+    use primitives::{H256, Blake2Hasher};
+    use support::{impl_outer_origin, assert_ok};
+    use runtime_primitives::{
+        BuildStorage,
+        traits::{BlakeTwo256, IdentityLookup},
+        testing::{Digest, DigestItem, Header}
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type Digest = Digest;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type Log = DigestItem;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type DustRemoval = ();
+        type TransferPayment = ();
+    }
+    impl Trait for Test {
+        type Event = ();
+    }
+    type Assets = Module<Test>;
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        system::GenesisConfig::<Test>::default().build_storage().unwrap().0.into()
+    }
+
+    // asset 1, all of it held by account 1
+    fn issue_asset() -> AssetId {
+        assert_ok!(Assets::issue(Origin::signed(1), 1000));
+        1
+    }
+
+    #[test]
+    fn issue_gives_the_total_supply_to_the_issuer() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(Assets::issue(Origin::signed(1), 0), Err("Total supply must be more than 0"));
+            let asset_id = issue_asset();
+            assert_eq!(Assets::owner(asset_id), Some(1));
+            assert_eq!(Assets::total_supply(asset_id), 1000);
+            assert_eq!(Assets::free_balance((asset_id, 1)), 1000);
+
+            assert_ok!(Assets::issue(Origin::signed(2), 5));
+            assert_eq!(Assets::assets_count(), 2);
+            assert!(Assets::is_issued(2));
+            assert!(!Assets::is_issued(3));
+            assert!(!Assets::is_issued(NATIVE_ASSET_ID));
+        });
+    }
+
+    #[test]
+    fn transfer_moves_free_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            let asset_id = issue_asset();
+            assert_ok!(Assets::transfer(Origin::signed(1), asset_id, 2, 300));
+            assert_eq!(Assets::free_balance((asset_id, 1)), 700);
+            assert_eq!(Assets::free_balance((asset_id, 2)), 300);
+
+            assert_eq!(Assets::transfer(Origin::signed(1), 2, 2, 10), Err("Asset is not issued"));
+            assert_eq!(Assets::transfer(Origin::signed(1), asset_id, 1, 10), Err("Cannot transfer to self"));
+            assert_eq!(Assets::transfer(Origin::signed(2), asset_id, 1, 301), Err("Balance is not enough"));
+            assert_eq!(Assets::free_balance((asset_id, 1)), 700);
+            assert_eq!(Assets::free_balance((asset_id, 2)), 300);
+            assert_eq!(Assets::total_supply(asset_id), 1000);
+        });
+    }
+
+    #[test]
+    fn withdraw_and_deposit_keep_the_total_supply() {
+        with_externalities(&mut new_test_ext(), || {
+            let asset_id = issue_asset();
+            assert_ok!(Assets::withdraw(asset_id, &1, 400));
+            assert_eq!(Assets::free_balance((asset_id, 1)), 600);
+            assert_eq!(Assets::withdraw(asset_id, &1, 601), Err("Balance is not enough"));
+            assert_eq!(Assets::free_balance((asset_id, 1)), 600);
+            assert_eq!(Assets::withdraw(NATIVE_ASSET_ID, &1, 1), Err("Asset is not issued"));
+
+            assert_ok!(Assets::deposit(asset_id, &2, 400));
+            assert_eq!(Assets::free_balance((asset_id, 2)), 400);
+            assert_eq!(Assets::deposit(2, &2, 1), Err("Asset is not issued"));
+            assert_eq!(Assets::total_supply(asset_id), 1000);
+        });
+    }
+
+    #[test]
+    fn unreserve_gives_back_at_most_the_reserved_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            let asset_id = issue_asset();
+            assert_ok!(Assets::reserve(asset_id, &1, 300));
+            assert_eq!(Assets::free_balance((asset_id, 1)), 700);
+            assert_eq!(Assets::reserved_balance((asset_id, 1)), 300);
+            assert_eq!(Assets::reserve(asset_id, &1, 701), Err("Balance is not enough"));
+            assert_eq!(Assets::reserved_balance((asset_id, 1)), 300);
+
+            Assets::unreserve(asset_id, &1, 100);
+            assert_eq!(Assets::reserved_balance((asset_id, 1)), 200);
+            Assets::unreserve(asset_id, &1, 500);
+            assert_eq!(Assets::free_balance((asset_id, 1)), 1000);
+            assert_eq!(Assets::reserved_balance((asset_id, 1)), 0);
+        });
+    }
+
+    #[test]
+    fn slash_reserved_takes_at_most_the_reserved_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            let asset_id = issue_asset();
+            assert_ok!(Assets::reserve(asset_id, &1, 300));
+            Assets::slash_reserved(asset_id, &1, 500);
+            assert_eq!(Assets::reserved_balance((asset_id, 1)), 0);
+            assert_eq!(Assets::free_balance((asset_id, 1)), 700);
+
+            // what was taken is held by the caller until deposited again
+            assert_eq!(Assets::total_supply(asset_id), 1000);
+            assert_ok!(Assets::deposit(asset_id, &2, 300));
+            assert_eq!(Assets::free_balance((asset_id, 1)) + Assets::free_balance((asset_id, 2)), Assets::total_supply(asset_id));
+        });
+    }
+}
@@ -1,14 +1,60 @@
 use parity_codec::{Decode, Encode};
 use support::{StorageValue, StorageMap, dispatch::Result, decl_module, decl_storage, decl_event, ensure};
-use support::traits::{Currency, WithdrawReason, ExistenceRequirement};
+use support::storage::RuntimeStorage;
+use support::storage::generator::Storage as GenericStorage;
+use support::traits::{Currency, ReservableCurrency, OnUnbalanced, Imbalance, WithdrawReason, ExistenceRequirement};
+use runtime_primitives::ApplyError;
 use runtime_primitives::traits::{As, CheckedSub, CheckedAdd, CheckedMul, CheckedDiv};
+use runtime_primitives::transaction_validity::TransactionValidity;
 use system::ensure_signed;
 use rstd::vec::Vec;
+use rstd::result;
+use crate::assets::{self, AssetId, NATIVE_ASSET_ID};
+
+/// Identifier of a DAO summoned in this module.
+pub type DaoId = u32;
+
+/// Layout version of the dao storage:
+/// - 0: before `StorageVersion` existed, the single dao of the first release, not keyed
+/// - 1: that dao moved to dao 0 of the keyed layout
+/// - 2: secondary indices of proposals and votes
+/// - 3: linked list of active members
+/// - 4: schedule of the daos with queued proposals
+pub const CURRENT_STORAGE_VERSION: u32 = 4;
+
+// the first version whose entries are keyed by dao
+const KEYED_LAYOUT_VERSION: u32 = 1;
+
+/// Entries upgraded per block while a storage migration runs.
+const MIGRATION_BATCH: u32 = 64;
+
+/// Most daos whose queued proposals are processed in one block.
+pub const MAX_DAOS_PER_BLOCK: u32 = 16;
+
+/// Most periods of energy decay applied to a member at once.
+const MAX_DECAY_PERIODS: u64 = 16;
+
+/// Most members of a dao visited for energy decay in one block.
+const MAX_DECAY_MEMBERS: u32 = 64;
+
+/// Most voters of processed proposals credited per dao in one block.
+const MAX_CREDITED_VOTERS: u32 = 64;
+
+/// Priority added to a dao vote in the pool, divided by the periods left to vote.
+const VOTE_PRIORITY: u64 = 1 << 20;
+
+/// Most members returned by one page of `active_members`.
+pub const MAX_MEMBERS_PAGE: u32 = 100;
+
+/// Most entries returned by one page of `proposal_list`.
+pub const MAX_PROPOSALS_PAGE: u32 = 100;
 
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Member {
     energy: u64,
+    // non-voting economic shares
+    loot: u64,
     highest_index_yes_vote: u32,
 }
 
@@ -18,20 +64,205 @@ pub struct AccessProposal<AccountId, Balance> {
     proposer: AccountId,
     applicant: AccountId,
     energies_requested: u64,
+    loot_requested: u64,
     mortgage: Balance,
+    // reserved from the proposer for the bytes this proposal occupies in storage
+    storage_deposit: Balance,
+    deposit_asset: AssetId,
     deposit: Balance,
+    payment_asset: AssetId,
+    payment_requested: Balance,
+    kind: ProposalKind,
     starting_period: u64,
     yes_votes: u64,
     no_votes: u64,
     processed: bool,
     did_pass: bool,
     aborted: bool,
-    detail: Vec<u8>,
+    detail: ContentId,
+}
+
+/// Position of a member in the list of active members. A member leaving the list keeps its
+/// `next`, so that paging from it resumes where it was.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct MemberLink<AccountId> {
+    prev: Option<AccountId>,
+    next: Option<AccountId>,
+    linked: bool,
+}
+
+/// An active member as listed by `active_members`.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct MemberInfo<AccountId> {
+    account: AccountId,
+    energy: u64,
+    loot: u64,
+    // 0 for members who joined before storage version 3
+    joined_period: u64,
+    proposals_voted: u32,
+}
+
+/// Self-declared identity of a member, bounded by `MaxProfileLength` once encoded.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Profile {
+    display_name: Vec<u8>,
+    contact: Vec<u8>,
+    avatar: Option<ContentId>,
+    // key for encrypted off-chain messages to the member
+    public_key: Option<[u8; 32]>,
+}
+
+/// The voters of a processed proposal or project stage at positions `next..end`, still to be
+/// noted as active and, if `rewarded`, given a vote reward point.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VoterCredit {
+    index: u32,
+    next: u32,
+    end: u32,
+    // `ProposalsProcessed` once the proposal was processed
+    processed: u32,
+    rewarded: bool,
+}
+
+/// The grant of a passed project stage, vesting linearly over `periods` from `start_period`.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct GrantStream<AccountId, Balance> {
+    recipient: AccountId,
+    asset: AssetId,
+    total: Balance,
+    claimed: Balance,
+    start_period: u64,
+    periods: u64,
+}
+
+/// What is kept of a proposal after it has been reaped.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ProposalRecord {
+    did_pass: bool,
+    yes_votes: u64,
+    no_votes: u64,
+}
+
+/// A parsed IPFS-style CID pointing at the off-chain description of a proposal.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ContentId {
+    version: u8,
+    // multicodec of the content
+    codec: u64,
+    // multihash function code
+    hash_code: u64,
+    digest: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ProposalKind {
+    Funding,
+    Whitelist(AssetId),
+}
+
+impl Default for ProposalKind {
+    fn default() -> Self { ProposalKind::Funding }
 }
 
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct ProjectProposal<AccountId, Balance> {
+    proposer: AccountId,
+    applicant: AccountId,
+    mortgage: Balance,
+    // reserved from the proposer for the bytes this proposal occupies in storage
+    storage_deposit: Balance,
+    starting_period: u64,
+    grant_asset: AssetId,
+    milestone_1_requested: Balance,
+    milestone_2_requested: Balance,
+    milestone_3_requested: Balance,
+    yes_votes: u64,
+    no_votes: u64,
+    processed: bool,
+    stage_did_pass: bool,
+    round: u64,
+    aborted: bool,
+    // the last milestone passed
+    completed: bool,
+    status: ProjectStatus,
+    detail: ContentId,
+}
+
+/// `Member` as stored in version 0.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+struct MemberV0 {
+    energy: u64,
+    highest_index_yes_vote: u32,
+}
+
+impl MemberV0 {
+    fn upgrade(self) -> Member {
+        Member {
+            energy: self.energy,
+            loot: 0,
+            highest_index_yes_vote: self.highest_index_yes_vote,
+        }
+    }
+}
+
+/// `AccessProposal` as stored in version 0.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+struct AccessProposalV0<AccountId, Balance> {
+    proposer: AccountId,
+    applicant: AccountId,
+    energies_requested: u64,
+    mortgage: Balance,
+    deposit: Balance,
+    starting_period: u64,
+    yes_votes: u64,
+    no_votes: u64,
+    processed: bool,
+    did_pass: bool,
+    aborted: bool,
+    detail: Vec<u8>,
+}
+
+impl<AccountId, Balance: Default> AccessProposalV0<AccountId, Balance> {
+    fn upgrade(self, detail: ContentId) -> AccessProposal<AccountId, Balance> {
+        // tributes were paid in the native currency, and nothing was reserved for storage
+        AccessProposal {
+            proposer: self.proposer,
+            applicant: self.applicant,
+            energies_requested: self.energies_requested,
+            loot_requested: 0,
+            mortgage: self.mortgage,
+            storage_deposit: Balance::default(),
+            deposit_asset: NATIVE_ASSET_ID,
+            deposit: self.deposit,
+            payment_asset: NATIVE_ASSET_ID,
+            payment_requested: Balance::default(),
+            kind: ProposalKind::Funding,
+            starting_period: self.starting_period,
+            yes_votes: self.yes_votes,
+            no_votes: self.no_votes,
+            processed: self.processed,
+            did_pass: self.did_pass,
+            aborted: self.aborted,
+            detail: detail,
+        }
+    }
+}
+
+/// `ProjectProposal` as stored in version 0.
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+struct ProjectProposalV0<AccountId, Balance> {
     proposer: AccountId,
     applicant: AccountId,
     mortgage: Balance,
@@ -49,6 +280,33 @@ pub struct ProjectProposal<AccountId, Balance> {
     detail: Vec<u8>,
 }
 
+impl<AccountId, Balance: Default> ProjectProposalV0<AccountId, Balance> {
+    fn upgrade(self, detail: ContentId) -> ProjectProposal<AccountId, Balance> {
+        // projects could not be aborted, the flag only marked the last milestone as passed
+        let completed = self.aborted && self.status == ProjectStatus::Milestone3;
+        ProjectProposal {
+            proposer: self.proposer,
+            applicant: self.applicant,
+            mortgage: self.mortgage,
+            storage_deposit: Balance::default(),
+            starting_period: self.starting_period,
+            grant_asset: NATIVE_ASSET_ID,
+            milestone_1_requested: self.milestone_1_requested,
+            milestone_2_requested: self.milestone_2_requested,
+            milestone_3_requested: self.milestone_3_requested,
+            yes_votes: self.yes_votes,
+            no_votes: self.no_votes,
+            processed: self.processed,
+            stage_did_pass: self.stage_did_pass,
+            round: self.round,
+            aborted: self.aborted && !completed,
+            completed: completed,
+            status: self.status,
+            detail: detail,
+        }
+    }
+}
+
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Copy)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub enum ProjectStatus {
@@ -62,7 +320,304 @@ impl Default for ProjectStatus {
     fn default() -> Self { ProjectStatus::Initialization }
 }
 
-pub trait Trait: balances::Trait + timestamp::Trait {
+/// A secondary index of the proposals of a dao, whose entries are `(is_project, index)`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ProposalList<AccountId> {
+    // unprocessed access proposals
+    OpenAccess,
+    // project proposals waiting for the vote on the given stage
+    OpenProject(ProjectStatus),
+    ByProposer(AccountId),
+    ByApplicant(AccountId),
+    // proposals the account voted on, a project once whatever the rounds
+    VotedBy(AccountId),
+}
+
+/// Failures of the dao calls. A dispatch result carries the message of the variant, which
+/// clients map back with `DaoError::from_message`, the list is exposed by `DaoApi::errors`.
+/// The discriminant is the code of a vote rejected by the transaction pool, so a variant keeps
+/// its number and a new one takes the next free number.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum DaoError {
+    DaoNotFound = 1,
+    NotMember = 2,
+    NotShareholder = 3,
+    ReceiverNotMember = 4,
+    AssetNotFound = 5,
+    AssetNotWhitelisted = 6,
+    AssetAlreadyWhitelisted = 7,
+    TributeAssetNotWhitelisted = 8,
+    PaymentAssetNotWhitelisted = 9,
+    GrantAssetNotWhitelisted = 10,
+    InvalidPeriodDuration = 11,
+    InvalidVotingPeriodLength = 12,
+    NoFounders = 13,
+    FounderNotSet = 14,
+    FounderEnergyZero = 15,
+    DuplicateFounder = 16,
+    FounderBalanceTooLow = 17,
+    FounderAllowanceTooLow = 18,
+    ApplicantAllowanceTooLow = 19,
+    AllowanceTooLow = 20,
+    NoAllowance = 21,
+    ApproveSelf = 22,
+    TransferToSelf = 23,
+    ApplicantNotSet = 24,
+    DetailTooLong = 25,
+    InvalidDetail = 26,
+    DraftNotFound = 27,
+    NotDraftApplicant = 28,
+    NotProposalApplicant = 29,
+    InvalidAccessProposalIndex = 30,
+    InvalidProjectProposalIndex = 31,
+    AccessProposalAborted = 32,
+    ProjectProposalAborted = 33,
+    AbortWindowPassed = 34,
+    NotInVotingPeriod = 35,
+    AlreadyVoted = 36,
+    ProjectNotProcessed = 37,
+    ProjectCompleted = 38,
+    FreePoolInsufficient = 39,
+    NothingToBurn = 40,
+    NothingToTransfer = 41,
+    EnergyTooLow = 42,
+    LootTooLow = 43,
+    YesVoteNotProcessed = 44,
+    VotesNotClosed = 45,
+    EnergyNotTransferable = 46,
+    ProposalNotSettled = 47,
+    RetentionNotPassed = 48,
+    MigrationInProgress = 49,
+    Overflow = 50,
+    ProfileTooLong = 51,
+    NoProfile = 52,
+    NoVoteRewards = 53,
+    NoGrantStream = 54,
+    NothingVested = 55,
+    ProjectNotCancellable = 56,
+    VotingNotStarted = 57,
+    InvalidDecayPercent = 58,
+}
+
+impl DaoError {
+    pub const ALL: [DaoError; 58] = [
+        DaoError::DaoNotFound,
+        DaoError::NotMember,
+        DaoError::NotShareholder,
+        DaoError::ReceiverNotMember,
+        DaoError::AssetNotFound,
+        DaoError::AssetNotWhitelisted,
+        DaoError::AssetAlreadyWhitelisted,
+        DaoError::TributeAssetNotWhitelisted,
+        DaoError::PaymentAssetNotWhitelisted,
+        DaoError::GrantAssetNotWhitelisted,
+        DaoError::InvalidPeriodDuration,
+        DaoError::InvalidVotingPeriodLength,
+        DaoError::NoFounders,
+        DaoError::FounderNotSet,
+        DaoError::FounderEnergyZero,
+        DaoError::DuplicateFounder,
+        DaoError::FounderBalanceTooLow,
+        DaoError::FounderAllowanceTooLow,
+        DaoError::ApplicantAllowanceTooLow,
+        DaoError::AllowanceTooLow,
+        DaoError::NoAllowance,
+        DaoError::ApproveSelf,
+        DaoError::TransferToSelf,
+        DaoError::ApplicantNotSet,
+        DaoError::DetailTooLong,
+        DaoError::InvalidDetail,
+        DaoError::DraftNotFound,
+        DaoError::NotDraftApplicant,
+        DaoError::NotProposalApplicant,
+        DaoError::InvalidAccessProposalIndex,
+        DaoError::InvalidProjectProposalIndex,
+        DaoError::AccessProposalAborted,
+        DaoError::ProjectProposalAborted,
+        DaoError::AbortWindowPassed,
+        DaoError::NotInVotingPeriod,
+        DaoError::AlreadyVoted,
+        DaoError::ProjectNotProcessed,
+        DaoError::ProjectCompleted,
+        DaoError::FreePoolInsufficient,
+        DaoError::NothingToBurn,
+        DaoError::NothingToTransfer,
+        DaoError::EnergyTooLow,
+        DaoError::LootTooLow,
+        DaoError::YesVoteNotProcessed,
+        DaoError::VotesNotClosed,
+        DaoError::EnergyNotTransferable,
+        DaoError::ProposalNotSettled,
+        DaoError::RetentionNotPassed,
+        DaoError::MigrationInProgress,
+        DaoError::Overflow,
+        DaoError::ProfileTooLong,
+        DaoError::NoProfile,
+        DaoError::NoVoteRewards,
+        DaoError::NoGrantStream,
+        DaoError::NothingVested,
+        DaoError::ProjectNotCancellable,
+        DaoError::VotingNotStarted,
+        DaoError::InvalidDecayPercent,
+    ];
+
+    pub fn message(&self) -> &'static str {
+        match *self {
+            DaoError::DaoNotFound => "The dao does not exist",
+            DaoError::NotMember => "Sender is not a member",
+            DaoError::NotShareholder => "Sender holds neither energy nor loot",
+            DaoError::ReceiverNotMember => "Receiver is not a member",
+            DaoError::AssetNotFound => "Asset does not exist",
+            DaoError::AssetNotWhitelisted => "Asset is not whitelisted",
+            DaoError::AssetAlreadyWhitelisted => "Asset is already whitelisted",
+            DaoError::TributeAssetNotWhitelisted => "Tribute asset is not whitelisted",
+            DaoError::PaymentAssetNotWhitelisted => "Payment asset is not whitelisted",
+            DaoError::GrantAssetNotWhitelisted => "Grant asset is not whitelisted",
+            DaoError::InvalidPeriodDuration => "Period duration must be more than 0",
+            DaoError::InvalidVotingPeriodLength => "Voting period length must be more than 0",
+            DaoError::NoFounders => "The dao must have at least one founder",
+            DaoError::FounderNotSet => "Founder is not set",
+            DaoError::FounderEnergyZero => "Founder energy must be more than 0",
+            DaoError::DuplicateFounder => "Duplicate founder",
+            DaoError::FounderBalanceTooLow => "Balance of the founder is not enough",
+            DaoError::FounderAllowanceTooLow => "Allowance of the founder is not enough",
+            DaoError::ApplicantAllowanceTooLow => "Allowance of the applicant is not enough",
+            DaoError::AllowanceTooLow => "Allowance is not enough",
+            DaoError::NoAllowance => "No allowance to revoke",
+            DaoError::ApproveSelf => "Cannot approve self",
+            DaoError::TransferToSelf => "Cannot transfer to self",
+            DaoError::ApplicantNotSet => "Applicant is not set",
+            DaoError::DetailTooLong => "Detail is too long",
+            DaoError::InvalidDetail => "Detail is not a valid content identifier",
+            DaoError::DraftNotFound => "This draft not exists!",
+            DaoError::NotDraftApplicant => "You are not the applicant of this draft!",
+            DaoError::NotProposalApplicant => "You are not the applicant of this access proposal!",
+            DaoError::InvalidAccessProposalIndex => "access proposal index is invalid!",
+            DaoError::InvalidProjectProposalIndex => "project proposal index is invalid!",
+            DaoError::AccessProposalAborted => "The access proposal has been aborted!",
+            DaoError::ProjectProposalAborted => "The project proposal has been aborted!",
+            DaoError::AbortWindowPassed => "Abort window has passed!",
+            DaoError::NotInVotingPeriod => "Not in voting period!",
+            DaoError::AlreadyVoted => "already voted!",
+            DaoError::ProjectNotProcessed => "Must forward project until be processed!",
+            DaoError::ProjectCompleted => "This project is completely done!",
+            DaoError::FreePoolInsufficient => "Free pool is insufficient!",
+            DaoError::NothingToBurn => "energies to burn must more than 0",
+            DaoError::NothingToTransfer => "energies to transfer must more than 0",
+            DaoError::EnergyTooLow => "Energy is not enough",
+            DaoError::LootTooLow => "Loot is not enough",
+            DaoError::YesVoteNotProcessed => "cant quit or transfer until highest index proposal member voted YES on is processed",
+            DaoError::VotesNotClosed => "cant transfer until the votes of the member are closed",
+            DaoError::EnergyNotTransferable => "Energy is not transferable in this dao",
+            DaoError::ProposalNotSettled => "The proposal is not settled",
+            DaoError::RetentionNotPassed => "Retention window has not passed!",
+            DaoError::MigrationInProgress => "Storage migration is in progress",
+            DaoError::Overflow => "Arithmetic overflow",
+            DaoError::ProfileTooLong => "Profile is too long",
+            DaoError::NoProfile => "Member has no profile",
+            DaoError::NoVoteRewards => "No vote rewards to claim",
+            DaoError::NoGrantStream => "Grant stream does not exist",
+            DaoError::NothingVested => "Nothing vested to claim",
+            DaoError::ProjectNotCancellable => "Project can only be cancelled by its applicant or after a failed stage",
+            DaoError::VotingNotStarted => "Voting has not started yet",
+            DaoError::InvalidDecayPercent => "Decay percent must be at most 100",
+        }
+    }
+
+    pub fn code(self) -> i8 {
+        self as i8
+    }
+
+    pub fn from_message(message: &str) -> Option<DaoError> {
+        Self::ALL.iter().cloned().find(|e| e.message() == message)
+    }
+}
+
+impl From<DaoError> for &'static str {
+    fn from(e: DaoError) -> &'static str {
+        e.message()
+    }
+}
+
+/// A consistency rule of the dao state, reported by `check_invariants` when it breaks.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Invariant {
+    // every index of `MembersArray` points at a member record
+    MembersArray,
+    // the sum over members
+    TotalEnergies,
+    TotalLoot,
+    // the sum over unprocessed access proposals
+    TotalEnergiesRequested,
+    // the mortgages of unprocessed access proposals and unfinished projects
+    MortgagePool,
+    // the tributes of unprocessed access proposals
+    DepositPool(AssetId),
+    // the grants of the project stages waiting to be processed
+    GrantLockedPool(AssetId),
+    // an asset held in the free pool is listed as a treasury asset
+    FreePool(AssetId),
+    // the active members list links exactly the members with energy
+    ActiveMembers,
+    // the budgets of the vote reward periods not fully claimed
+    VoteRewardPool,
+    // the unclaimed part of the open grant streams
+    StreamLockedPool(AssetId),
+}
+
+/// Weight of the dao calls, counted as one unit per storage read and `WRITE` units per
+/// storage write. A call is charged `weight * WeightFee` on top of the transaction fee.
+pub mod weight {
+    pub const READ: u64 = 1;
+    pub const WRITE: u64 = 4;
+    // adding an entry to or removing one from a proposal list
+    pub const LIST_INSERT: u64 = 2 * READ + 3 * WRITE;
+    pub const LIST_REMOVE: u64 = 3 * READ + 4 * WRITE;
+
+    pub const SUMMON: u64 = 4 * READ + 19 * WRITE;
+    pub const SUMMON_PER_FOUNDER: u64 = 7 * READ + 11 * WRITE;
+    pub const APPROVE: u64 = 3 * READ + 3 * WRITE;
+    pub const DONATE: u64 = 5 * READ + 3 * WRITE;
+    pub const SUBMIT_PROPOSAL: u64 = 11 * READ + 5 * WRITE + 3 * LIST_INSERT;
+    pub const SUBMIT_DRAFT: u64 = 6 * READ + 4 * WRITE;
+    pub const SPONSOR: u64 = 12 * READ + 7 * WRITE + 3 * LIST_INSERT;
+    pub const CANCEL_DRAFT: u64 = 2 * READ + 3 * WRITE;
+    pub const FORWARD_TO_MILESTONE: u64 = 8 * READ + 4 * WRITE + LIST_INSERT;
+    pub const VOTE: u64 = 26 * READ + 14 * WRITE + LIST_INSERT;
+    pub const ABORT: u64 = 5 * READ + 3 * WRITE;
+    pub const RAGE_QUIT: u64 = 9 * READ + 7 * WRITE;
+    pub const RAGE_QUIT_PER_ASSET: u64 = READ + 2 * WRITE;
+    pub const TRANSFER_ENERGY: u64 = 11 * READ + 8 * WRITE;
+    pub const REAP: u64 = 6 * READ + 5 * WRITE + 2 * LIST_REMOVE;
+    pub const REAP_PER_VOTER: u64 = READ + 2 * WRITE + LIST_REMOVE;
+    pub const SET_PROFILE: u64 = 3 * READ + 3 * WRITE;
+    pub const CLEAR_PROFILE: u64 = 2 * READ + 3 * WRITE;
+    pub const CLAIM_VOTE_REWARDS: u64 = 5 * READ + 4 * WRITE;
+    pub const CLAIM_VOTE_REWARDS_PER_PERIOD: u64 = 4 * READ + 4 * WRITE;
+    pub const CLAIM_STREAM: u64 = 3 * READ + 3 * WRITE;
+    pub const CANCEL_PROJECT: u64 = 10 * READ + 5 * WRITE;
+    pub const CANCEL_PROJECT_PER_STREAM: u64 = 3 * READ + 4 * WRITE;
+}
+
+/// Reputation gained or lost by the accounts of a proposal when it is processed. Gains need a
+/// proposal with a tribute, a payment or a grant.
+pub mod reputation {
+    // the proposer of an access proposal, or the applicant of a project stage
+    pub const PASSED: i64 = 2;
+    pub const FAILED: i64 = -1;
+    // the applicant withdrew the proposal
+    pub const ABORTED: i64 = -2;
+    // both the proposer and the applicant of a project passing its last milestone
+    pub const COMPLETED: i64 = 10;
+
+    /// Extra vote weight is capped at this percentage of the energy.
+    pub const MAX_VOTE_BONUS: u64 = 100;
+}
+
+pub trait Trait: balances::Trait + timestamp::Trait + assets::Trait {
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 }
 
@@ -71,69 +626,218 @@ decl_event! (
     where AccountId = <T as system::Trait>::AccountId,
     Balance = <T as balances::Trait>::Balance,
     {
-        SummonComplete(AccountId),
-        Approval(AccountId, Balance),
-        Donate(AccountId, Balance),
-        SubmitAccessProposal(AccountId, AccountId, u64, Balance, u64),
-        SubmitProjectProposal(AccountId, AccountId, Balance, Balance, Balance, u64),
-        ForwardToMilestone(AccountId, u32, ProjectStatus, u64, u64),
-        AccessVote(AccountId, u32, bool),
-        ProjectVote(AccountId, u32, ProjectStatus, u64, bool),
-        AccessAbort(u32),
-        RageQuit(AccountId, u64, Balance),
-        NewMember(AccountId, u64),
-        ProcessAccessProposal(u32, AccountId, AccountId, Balance, u64, bool),
-        ProcessProjectProposal(u32, AccountId, AccountId, ProjectStatus, u64, bool, Balance),
+        SummonComplete(DaoId, AccountId),
+        Approval(DaoId, AccountId, AccountId, AssetId, Balance),
+        Donate(DaoId, AccountId, AssetId, Balance),
+        SubmitAccessProposal(DaoId, AccountId, AccountId, u64, AssetId, Balance, u64),
+        PaymentRequested(DaoId, u32, AssetId, Balance),
+        SubmitWhitelistProposal(DaoId, u32, AssetId),
+        TokenWhitelisted(DaoId, AssetId),
+        SubmitProjectProposal(DaoId, AccountId, AccountId, AssetId, Balance, Balance, Balance, u64),
+        SubmitAccessDraft(DaoId, u32, AccountId),
+        SubmitProjectDraft(DaoId, u32, AccountId),
+        SponsorAccessProposal(DaoId, u32, AccountId, u32),
+        SponsorProjectProposal(DaoId, u32, AccountId, u32),
+        CancelAccessDraft(DaoId, u32),
+        CancelProjectDraft(DaoId, u32),
+        ReapProposal(DaoId, bool, u32, AccountId, Balance),
+        ForwardToMilestone(DaoId, AccountId, u32, ProjectStatus, u64, u64),
+        AccessVote(DaoId, AccountId, u32, bool),
+        ProjectVote(DaoId, AccountId, u32, ProjectStatus, u64, bool),
+        AccessAbort(DaoId, u32),
+        RageQuit(DaoId, AccountId, u64, u64),
+        Redeem(DaoId, AccountId, AssetId, Balance),
+        NewMember(DaoId, AccountId, u64),
+        LootGranted(DaoId, AccountId, u64),
+        TransferEnergy(DaoId, AccountId, AccountId, u64),
+        ProcessAccessProposal(DaoId, u32, AccountId, AccountId, Balance, u64, bool),
+        ProcessProjectProposal(DaoId, u32, AccountId, AccountId, ProjectStatus, u64, bool, Balance),
+        // a proposal whose settlement would over- or underflow is closed as failed, giving back what it holds
+        ProcessingSkipped(DaoId, bool, u32),
+        StorageMigrated(u32),
+        InvariantBroken(DaoId, Invariant),
+        ProfileSet(DaoId, AccountId, Balance),
+        ProfileCleared(DaoId, AccountId),
+        ReputationChanged(DaoId, AccountId, i64),
+        VoteRewardsClaimed(DaoId, AccountId, Balance),
+        EnergyDecayed(DaoId, AccountId, u64),
+        EnergyRestored(DaoId, AccountId, u64),
+        GrantStreamStarted(DaoId, u32, ProjectStatus, AccountId, AssetId, Balance, u64),
+        StreamClaimed(DaoId, u32, ProjectStatus, AccountId, Balance),
+        // the project, the stage, and what went back to the free pool
+        StreamCancelled(DaoId, u32, ProjectStatus, Balance),
+        ProjectCancelled(DaoId, u32, AccountId),
     }
 );
 
 decl_storage! {
     trait Store for Module<T: Trait> as Dao {
-        // assert pool
-        FreePool get(free_pool): T::Balance;
-        MortgagePool get(mortgage_pool): T::Balance;
-        DepositPool get(deposit_pool): T::Balance;
-        GrantLockedPool get(grant_locked_pool): T::Balance;
+        // daos, the one summoned before daos were keyed is imported as dao 0
+        DaosCount get(daos_count): DaoId;
+        // reserved from the summoner, for the storage a dao takes
+        SummonDeposit get(summon_deposit) config(): T::Balance;
+        DaoDeposit get(dao_deposit): map DaoId => T::Balance;
+
+        // ring of the daos with queued proposals, visited in turn by on_initialize
+        ScheduledDaos get(scheduled_daos): map u32 => DaoId;
+        ScheduledDaosHead get(scheduled_daos_head): u32;
+        ScheduledDaosLength get(scheduled_daos_length): u32;
+        IsScheduled get(is_scheduled): map DaoId => bool;
+
+        // layout of the stored structs, a new chain starts with the current one
+        StorageVersion get(storage_version) build(|_: &GenesisConfig<T>| CURRENT_STORAGE_VERSION): u32;
+        // the dao and the index of the next entry to migrate
+        MigrationCursor get(migration_cursor): (DaoId, u32);
+
+        // assert pool, the mortgage is always paid in the native currency
+        FreePool get(free_pool): map (DaoId, AssetId) => T::Balance;
+        MortgagePool get(mortgage_pool): map DaoId => T::Balance;
+        DepositPool get(deposit_pool): map (DaoId, AssetId) => T::Balance;
+        GrantLockedPool get(grant_locked_pool): map (DaoId, AssetId) => T::Balance;
+        // assets that have ever been credited to the free pool
+        TreasuryAssets get(treasury_assets): map DaoId => Vec<AssetId>;
+        // assets accepted as tribute, donation or payment, managed by whitelist proposals
+        TokenWhitelist get(token_whitelist): map (DaoId, AssetId) => bool;
 
         // energy pool - voting weight
-        TotalEnergies get(total_energies): u64;
-        TotalEnergiesRequested get(total_energies_requested): u64;
+        TotalEnergies get(total_energies): map DaoId => u64;
+        TotalEnergiesRequested get(total_energies_requested): map DaoId => u64;
+        // loot pool - economic claim without voting weight
+        TotalLoot get(total_loot): map DaoId => u64;
 
-        // allowance
-        Allowance get(allowance): map T::AccountId => T::Balance;
+        // allowance of (applicant, spender), backed by funds reserved from the applicant
+        Allowance get(allowance): map (DaoId, T::AccountId, T::AccountId, AssetId) => T::Balance;
 
         // members
-        MembersCount get(members_count): u32;
-        MembersArray get(members_array): map u32 => T::AccountId;
-        Members get(members): map T::AccountId => Member;
+        MembersCount get(members_count): map DaoId => u32;
+        MembersArray get(members_array): map (DaoId, u32) => T::AccountId;
+        Members get(members): map (DaoId, T::AccountId) => Member;
+        // members with energy, in order of activation
+        ActiveMembersHead get(active_members_head): map DaoId => Option<T::AccountId>;
+        ActiveMembersTail get(active_members_tail): map DaoId => Option<T::AccountId>;
+        ActiveMembersCount get(active_members_count): map DaoId => u32;
+        MemberLinks get(member_links): map (DaoId, T::AccountId) => MemberLink<T::AccountId>;
+        JoinedPeriod get(joined_period): map (DaoId, T::AccountId) => u64;
+        // distinct proposals voted on, reaped ones included
+        ProposalsVoted get(proposals_voted): map (DaoId, T::AccountId) => u32;
+        Reputation get(reputation): map (DaoId, T::AccountId) => i64;
+
+        // a vote earns a point in the period its proposal is settled, and the budget of a
+        // period is shared by its points
+        VotePoints get(vote_points): map (DaoId, u64, T::AccountId) => u32;
+        PeriodVotePoints get(period_vote_points): map (DaoId, u64) => u32;
+        VoteRewardPeriods get(vote_reward_periods): map (DaoId, T::AccountId) => Vec<u64>;
+        // the budget left and the points not claimed yet, from the first claim of a period
+        PeriodVoteRewards get(period_vote_rewards): map (DaoId, u64) => Option<(T::Balance, u32)>;
+        OpenRewardPeriods get(open_reward_periods): map DaoId => Vec<u64>;
+        // native budgets taken out of the free pool and not claimed yet
+        VoteRewardPool get(vote_reward_pool): map DaoId => T::Balance;
+
+        // access proposals and project stages processed so far, and the count when a member last
+        // joined, voted or had a proposal it voted on processed
+        ProposalsProcessed get(proposals_processed): map DaoId => u32;
+        ActiveSince get(active_since): map (DaoId, T::AccountId) => u32;
+        // the period a member last joined or voted in, and how many periods since then its
+        // energy decayed for
+        LastVotePeriod get(last_vote_period): map (DaoId, T::AccountId) => u64;
+        DecayedPeriods get(decayed_periods): map (DaoId, T::AccountId) => u64;
+        // energy burned by inactivity, given back on the next vote
+        DecayedEnergy get(decayed_energy): map (DaoId, T::AccountId) => u64;
+        // the active member the next decay visit of a dao starts from, if a sweep is under way
+        DecayCursor get(decay_cursor): map DaoId => Option<T::AccountId>;
+
+        // streamed grants by project and stage, outliving the reaping of their project
+        GrantStreams get(grant_streams): map (DaoId, u32, ProjectStatus) => Option<GrantStream<T::AccountId, T::Balance>>;
+        StreamLockedPool get(stream_locked_pool): map (DaoId, AssetId) => T::Balance;
+
+        Profiles get(profiles): map (DaoId, T::AccountId) => Option<Profile>;
+        // reserved from the member while its profile is kept
+        ProfileDeposit get(profile_deposit): map (DaoId, T::AccountId) => T::Balance;
+
+        // drafts waiting for a member to sponsor them
+        AccessDraftsCount get(access_drafts_count): map DaoId => u32;
+        AccessDrafts get(access_drafts): map (DaoId, u32) => Option<AccessProposal<T::AccountId, T::Balance>>;
+        ProjectDraftsCount get(project_drafts_count): map DaoId => u32;
+        ProjectDrafts get(project_drafts): map (DaoId, u32) => Option<ProjectProposal<T::AccountId, T::Balance>>;
 
         // access proposal
-        AccessProposalsCount get(access_proposals_count): u32;
-        AccessProposals get(access_proposals): map u32 => AccessProposal<T::AccountId, T::Balance>;
-        ProcessedAccessProposalsCount get(processed_access_proposals_count): u32;
+        AccessProposalsCount get(access_proposals_count): map DaoId => u32;
+        AccessProposals get(access_proposals): map (DaoId, u32) => AccessProposal<T::AccountId, T::Balance>;
+        ProcessedAccessProposalsCount get(processed_access_proposals_count): map DaoId => u32;
 
         // project proposal
-        ProjectProposalsCount get(project_proposals_count): u32;
-        ProjectProposals get(project_proposals): map u32 => ProjectProposal<T::AccountId, T::Balance>;
+        ProjectProposalsCount get(project_proposals_count): map DaoId => u32;
+        ProjectProposals get(project_proposals): map (DaoId, u32) => ProjectProposal<T::AccountId, T::Balance>;
+
+        ProjectsProcessQueue get(projects_process_queue): map (DaoId, u32) => u32;
+        UnprocessedQueueHead get(unprocssed_queue_head): map DaoId => u32;
+        UnprocessedQueueLength get(unprocssed_queue_length): map DaoId => u32;
+
+
+        // secondary indices, each a map of positions with a length so that an update only reads
+        // and writes single entries. An entry knows its position, and is removed by moving the
+        // last entry of its list in its place.
+        ProposalLists get(proposal_list_entry): map (DaoId, ProposalList<T::AccountId>, u32) => (bool, u32);
+        ProposalListLength get(proposal_list_length): map (DaoId, ProposalList<T::AccountId>) => u32;
+        ProposalListPosition get(proposal_list_position): map (DaoId, ProposalList<T::AccountId>, (bool, u32)) => Option<u32>;
 
-        ProjectsProcessQueue get(projects_process_queue): map u32 => u32;
-        UnprocessedQueueHead get(unprocssed_queue_head): u32;
-        UnprocessedQueueLength get(unprocssed_queue_length): u32;
-        
+        // archive of reaped proposals
+        AccessProposalsArchive get(access_proposals_archive): map (DaoId, u32) => Option<ProposalRecord>;
+        ProjectProposalsArchive get(project_proposals_archive): map (DaoId, u32) => Option<ProposalRecord>;
 
         // vote
-        VotesForAccess get(votes_for_access): map (u32, T::AccountId) => Option<bool>;
-        VotesForProject get(votes_for_project): map (u32, T::AccountId, ProjectStatus, u64) => Option<bool>;
+        // voters of a proposal by position, so that a vote only writes its own entry
+        AccessVoters get(access_voter): map (DaoId, u32, u32) => T::AccountId;
+        AccessVotersCount get(access_voters_count): map (DaoId, u32) => u32;
+        ProjectVoters get(project_voter): map (DaoId, u32, u32) => (T::AccountId, ProjectStatus, u64);
+        ProjectVotersCount get(project_voters_count): map (DaoId, u32) => u32;
+        // the voters of the stages of a project credited so far
+        ProjectVotersCredited get(project_voters_credited): map (DaoId, u32) => u32;
+        // the voters of the last processed access proposal or project stage still to be credited
+        PendingCredits get(pending_credit): map (DaoId, bool) => Option<VoterCredit>;
+        VotesForAccess get(votes_for_access): map (DaoId, u32, T::AccountId) => Option<bool>;
+        VotesForProject get(votes_for_project): map (DaoId, u32, T::AccountId, ProjectStatus, u64) => Option<bool>;
+        // the period until which the energy of a member is counted in an open vote
+        VoteLockedUntil get(vote_locked_until): map (DaoId, T::AccountId) => u64;
 
         // detail
-        Summoner get(summoner): Option<T::AccountId>;
-        SummoningTime get(summonging_time): T::Moment;
+        Summoner get(summoner): map DaoId => Option<T::AccountId>;
+        SummoningTime get(summonging_time): map DaoId => T::Moment;
 
         // config
-        PeriodDuration get(period_duration) config(): T::Moment;
-        VotingPeriodLength get(voting_period_length) config(): u64;
-        AbortWindow get(abort_window) config(): u64;
-        ProposalMortgage get(proposal_mortgage) config(): T::Balance;
+        PeriodDuration get(period_duration): map DaoId => T::Moment;
+        VotingPeriodLength get(voting_period_length): map DaoId => u64;
+        AbortWindow get(abort_window): map DaoId => u64;
+        ProposalMortgage get(proposal_mortgage): map DaoId => T::Balance;
+        EnergyTransferable get(energy_transferable): map DaoId => bool;
+        // energy of members missing this many processed proposals in a row decays by
+        // `DecayPercent` per period since their last vote, 0 disables decay
+        DecayAfterMissed get(decay_after_missed): map DaoId => u32;
+        DecayPercent get(decay_percent): map DaoId => u64;
+        // periods a passed stage grant vests over, 0 pays it at once
+        GrantStreamPeriods get(grant_stream_periods): map DaoId => u64;
+
+        // chain config
+        MaxDetailLength get(max_detail_length) config(): u32;
+        MaxProfileLength get(max_profile_length) config(): u32;
+        ByteDeposit get(byte_deposit) config(): T::Balance;
+        RetentionPeriods get(retention_periods) config(): u64;
+        ReapReward get(reap_reward) config(): T::Balance;
+        // fee per unit of call weight, and how much of the fees of a vote a member gets back
+        WeightFee get(weight_fee) config(): T::Balance;
+        VoteFeeRefund get(vote_fee_refund) config(): T::Balance;
+        // fees paid so far by the extrinsic of the given index, for the vote fee refund
+        ExtrinsicFees get(extrinsic_fees): (Option<u32>, T::Balance);
+        // extra vote weight, in percent of the energy, per point of positive reputation
+        ReputationVoteBonus get(reputation_vote_bonus) config(): u64;
+        // native reward shared by the votes of a period, per dao
+        VoteRewardBudget get(vote_reward_budget) config(): T::Balance;
+    }
+}
+
+fn push_unique<V: PartialEq>(list: &mut Vec<V>, value: V) {
+    if !list.contains(&value) {
+        list.push(value);
     }
 }
 
@@ -141,449 +845,952 @@ decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
         fn deposit_event<T>() = default;
 
-        pub fn summon(origin) -> Result {
+        pub fn summon(
+            origin,
+            period_duration: T::Moment,
+            voting_period_length: u64,
+            abort_window: u64,
+            proposal_mortgage: T::Balance,
+            energy_transferable: bool,
+            decay_after_missed: u32,
+            decay_percent: u64,
+            grant_stream_periods: u64,
+            founders: Vec<(T::AccountId, u64, T::Balance)>
+        ) -> Result {
             let sender = ensure_signed(origin)?;
+            let founders_weight = weight::SUMMON_PER_FOUNDER.checked_mul(founders.len() as u64).ok_or(DaoError::Overflow)?;
+            Self::charge_weight(&sender, weight::SUMMON + founders_weight)?;
+            Self::ensure_storage_migrated()?;
+
+            ensure!(period_duration > <T::Moment as As<u64>>::sa(0), DaoError::InvalidPeriodDuration.into());
+            ensure!(voting_period_length > 0, DaoError::InvalidVotingPeriodLength.into());
+            ensure!(decay_percent <= 100, DaoError::InvalidDecayPercent.into());
+            ensure!(!founders.is_empty(), DaoError::NoFounders.into());
+
+            let dao_id = Self::daos_count();
+            let new_daos_count = dao_id.checked_add(1).ok_or(DaoError::Overflow)?;
 
-            ensure!(Self::summoner() == None, "The dao has been summoned!");
+            // validate founders before touching any balance or storage
+            let mut total_energies: u64 = 0;
+            let mut new_free_pool = <T::Balance as As<u64>>::sa(0);
+            for (i, (founder, energy, tribute)) in founders.iter().enumerate() {
+                ensure!(*founder != T::AccountId::default(), DaoError::FounderNotSet.into());
+                ensure!(*energy > 0, DaoError::FounderEnergyZero.into());
+                ensure!(!founders[..i].iter().any(|f| f.0 == *founder), DaoError::DuplicateFounder.into());
+
+                total_energies = total_energies.checked_add(*energy).ok_or(DaoError::Overflow)?;
+                if *tribute > <T::Balance as As<u64>>::sa(0) {
+                    if *founder == sender {
+                        ensure!(<balances::Module<T>>::free_balance(founder) >= *tribute, DaoError::FounderBalanceTooLow.into());
+                    } else {
+                        ensure!(Self::allowance((dao_id, founder.clone(), sender.clone(), NATIVE_ASSET_ID)) >= *tribute, DaoError::FounderAllowanceTooLow.into());
+                    }
+                    new_free_pool = new_free_pool.checked_add(tribute).ok_or(DaoError::Overflow)?;
+                }
+            }
 
             let now = <timestamp::Module<T>>::get();
-            let summoner = Member{
-                energy: 1,
-                highest_index_yes_vote: 0,
-            };
+            let summon_deposit = Self::summon_deposit();
+
+            // the deposit and the tribute of the sender are the only transfers that can fail, so they go first
+            let sender_tribute = founders.iter().find(|f| f.0 == sender).map(|f| f.2).unwrap_or_else(|| <T::Balance as As<u64>>::sa(0));
+            Self::take_funds(&sender, summon_deposit, sender_tribute)?;
+            for (founder, _, tribute) in founders.iter() {
+                if *tribute > <T::Balance as As<u64>>::sa(0) && *founder != sender {
+                    Self::take_reserved_asset(NATIVE_ASSET_ID, founder, *tribute);
+                    <Allowance<T>>::mutate((dao_id, founder.clone(), sender.clone(), NATIVE_ASSET_ID), |n| *n -= *tribute);
+                }
+            }
+
+            <PeriodDuration<T>>::insert(dao_id, period_duration);
+            <VotingPeriodLength<T>>::insert(dao_id, voting_period_length);
+            <AbortWindow<T>>::insert(dao_id, abort_window);
+            <ProposalMortgage<T>>::insert(dao_id, proposal_mortgage);
+            <EnergyTransferable<T>>::insert(dao_id, energy_transferable);
+            <DecayAfterMissed<T>>::insert(dao_id, decay_after_missed);
+            <DecayPercent<T>>::insert(dao_id, decay_percent);
+            <GrantStreamPeriods<T>>::insert(dao_id, grant_stream_periods);
+
+            <Summoner<T>>::insert(dao_id, sender.clone());
+            <SummoningTime<T>>::insert(dao_id, now.clone());
+            <DaoDeposit<T>>::insert(dao_id, summon_deposit);
+            <TotalEnergies<T>>::insert(dao_id, total_energies);
+            <FreePool<T>>::insert((dao_id, NATIVE_ASSET_ID), new_free_pool);
+            <TokenWhitelist<T>>::insert((dao_id, NATIVE_ASSET_ID), true);
+            Self::note_treasury_asset(dao_id, NATIVE_ASSET_ID);
+            <DaosCount<T>>::put(new_daos_count);
+
+            for (founder, energy, _) in founders.into_iter() {
+                Self::add_new_member(dao_id, founder, energy, 0);
+            }
+
+            Self::deposit_event(RawEvent::SummonComplete(dao_id, sender));
+            Ok(())
+        }
+
+        // the dao may not be summoned yet, so that founders can approve their tribute in advance
+        pub fn applicant_approve(origin, dao_id: DaoId, spender: T::AccountId, asset_id: AssetId, value: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::APPROVE)?;
+            Self::ensure_storage_migrated()?;
+
+            ensure!(Self::asset_exists(asset_id), DaoError::AssetNotFound.into());
+            ensure!(sender != spender, DaoError::ApproveSelf.into());
+
+            let allowance_key = (dao_id, sender.clone(), spender.clone(), asset_id);
+            let current = Self::allowance(allowance_key.clone());
+            if value > current {
+                Self::reserve_asset(asset_id, &sender, value - current)?;
+            } else if value < current {
+                Self::unreserve_asset(asset_id, &sender, current - value);
+            }
+            <Allowance<T>>::insert(allowance_key, value);
+
+            Self::deposit_event(RawEvent::Approval(dao_id, sender, spender, asset_id, value));
+            Ok(())
+        }
 
-            <Summoner<T>>::put(sender.clone());
-            <SummoningTime<T>>::put(now.clone());
-            <TotalEnergies<T>>::put(summoner.energy);
-            <Members<T>>::insert(sender.clone(), summoner);
-            <MembersArray<T>>::insert(Self::members_count(), sender.clone());
-            <MembersCount<T>>::mutate(|n| *n += 1);
+        pub fn decrease_allowance(origin, dao_id: DaoId, spender: T::AccountId, asset_id: AssetId, value: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::APPROVE)?;
+            Self::ensure_storage_migrated()?;
+
+            let allowance_key = (dao_id, sender.clone(), spender.clone(), asset_id);
+            let new_allowance = Self::allowance(allowance_key.clone()).checked_sub(&value).ok_or(DaoError::AllowanceTooLow)?;
 
-            Self::deposit_event(RawEvent::SummonComplete(sender));
+            Self::unreserve_asset(asset_id, &sender, value);
+            <Allowance<T>>::insert(allowance_key, new_allowance);
+
+            Self::deposit_event(RawEvent::Approval(dao_id, sender, spender, asset_id, new_allowance));
             Ok(())
         }
 
-        pub fn applicant_approve(origin, value: T::Balance) -> Result {
+        pub fn revoke(origin, dao_id: DaoId, spender: T::AccountId, asset_id: AssetId) -> Result {
             let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::APPROVE)?;
+            Self::ensure_storage_migrated()?;
+
+            let allowance_key = (dao_id, sender.clone(), spender.clone(), asset_id);
+            ensure!(<Allowance<T>>::exists(allowance_key.clone()), DaoError::NoAllowance.into());
 
-            <Allowance<T>>::insert(sender.clone(), value);
+            let current = <Allowance<T>>::take(allowance_key);
+            Self::unreserve_asset(asset_id, &sender, current);
 
-            Self::deposit_event(RawEvent::Approval(sender, value));
+            Self::deposit_event(RawEvent::Approval(dao_id, sender, spender, asset_id, <T::Balance as As<u64>>::sa(0)));
             Ok(())
         }
 
-        pub fn donate(origin, value: T::Balance) -> Result {
+        pub fn donate(origin, dao_id: DaoId, asset_id: AssetId, value: T::Balance) -> Result {
             let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::DONATE)?;
+            Self::ensure_storage_migrated()?;
 
-            let new_free_pool = Self::free_pool().checked_add(&value).ok_or("overflow in calculating free pool")?;
-            let _ = <balances::Module<T> as Currency<_>>::withdraw(&sender, value, WithdrawReason::Reserve, ExistenceRequirement::KeepAlive)?;
-            <FreePool<T>>::put(new_free_pool);
-    
-            Self::deposit_event(RawEvent::Donate(sender, value));
+            ensure!(Self::dao_exists(dao_id), DaoError::DaoNotFound.into());
+            ensure!(Self::token_whitelist((dao_id, asset_id)), DaoError::AssetNotWhitelisted.into());
+
+            let new_free_pool = Self::free_pool((dao_id, asset_id)).checked_add(&value).ok_or(DaoError::Overflow)?;
+            Self::withdraw_asset(asset_id, &sender, value)?;
+            <FreePool<T>>::insert((dao_id, asset_id), new_free_pool);
+            Self::note_treasury_asset(dao_id, asset_id);
+
+            Self::deposit_event(RawEvent::Donate(dao_id, sender, asset_id, value));
             Ok(())
         }
 
         pub fn submit_access_proposal(
-            origin, 
+            origin,
+            dao_id: DaoId,
             applicant: T::AccountId,
+            deposit_asset: AssetId,
             deposit: T::Balance,
             energies_requested: u64,
             detail: Vec<u8>
         ) -> Result {
             let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SUBMIT_PROPOSAL)?;
+            Self::ensure_storage_migrated()?;
+            let detail = Self::parse_content_id(&detail)?;
+
+            Self::create_access_proposal(dao_id, sender, applicant, ProposalKind::Funding, deposit_asset, deposit, NATIVE_ASSET_ID, <T::Balance as As<u64>>::sa(0), energies_requested, 0, detail, false)
+        }
+
+        pub fn submit_funding_proposal(
+            origin,
+            dao_id: DaoId,
+            applicant: T::AccountId,
+            tribute_asset: AssetId,
+            tribute_offered: T::Balance,
+            payment_asset: AssetId,
+            payment_requested: T::Balance,
+            shares_requested: u64,
+            loot_requested: u64,
+            detail: Vec<u8>
+        ) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SUBMIT_PROPOSAL)?;
+            Self::ensure_storage_migrated()?;
+            let detail = Self::parse_content_id(&detail)?;
 
-            ensure!(Self::is_member(&sender), "Sender is not a member");
-            ensure!(applicant != T::AccountId::default(), "Applicant is not set");
-            ensure!(Self::allowance(applicant.clone()) >= deposit, "Allowance of the applicant is not enough");
+            Self::create_access_proposal(dao_id, sender, applicant, ProposalKind::Funding, tribute_asset, tribute_offered, payment_asset, payment_requested, shares_requested, loot_requested, detail, false)
+        }
 
-            let total_energies_requested = Self::total_energies_requested();
-            let new_total_energies_requested = total_energies_requested.checked_add(energies_requested).ok_or("overflow in calculating energies")?;
+        pub fn submit_whitelist_proposal(origin, dao_id: DaoId, asset_id: AssetId, detail: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SUBMIT_PROPOSAL)?;
+            Self::ensure_storage_migrated()?;
+            let detail = Self::parse_content_id(&detail)?;
 
-            let mut new_deposit_pool = Self::deposit_pool();
-            if deposit > <T::Balance as As<u64>>::sa(0) {
-                new_deposit_pool = new_deposit_pool.checked_add(&deposit).ok_or("overflow in calculating deposit pool")?;
-            }
+            ensure!(Self::asset_exists(asset_id), DaoError::AssetNotFound.into());
+            ensure!(!Self::token_whitelist((dao_id, asset_id)), DaoError::AssetAlreadyWhitelisted.into());
 
-            let mut this_starting_period: u64 = Self::get_current_period();
-            if Self::access_proposals_count() != 0 && Self::access_proposals(Self::access_proposals_count() - 1).starting_period > this_starting_period {
-                this_starting_period = Self::access_proposals(Self::access_proposals_count()-1).starting_period + 1;
-            }
+            Self::create_access_proposal(dao_id, sender.clone(), sender, ProposalKind::Whitelist(asset_id), NATIVE_ASSET_ID, <T::Balance as As<u64>>::sa(0), NATIVE_ASSET_ID, <T::Balance as As<u64>>::sa(0), 0, 0, detail, false)
+        }
 
-            let new_mortgage_pool = Self::mortgage_pool().checked_add(&Self::proposal_mortgage()).ok_or("overflow in calculating mortgage pool")?;
+        pub fn submit_project_proposal(
+            origin,
+            dao_id: DaoId,
+            applicant: T::AccountId,
+            grant_asset: AssetId,
+            milestone_1_requested: T::Balance,
+            milestone_2_requested: T::Balance,
+            milestone_3_requested: T::Balance,
+            detail: Vec<u8>
+        ) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SUBMIT_PROPOSAL)?;
+            Self::ensure_storage_migrated()?;
+            let detail = Self::parse_content_id(&detail)?;
+
+            Self::create_project_proposal(dao_id, sender, applicant, grant_asset, milestone_1_requested, milestone_2_requested, milestone_3_requested, detail)
+        }
+
+        pub fn submit_access_draft(
+            origin,
+            dao_id: DaoId,
+            tribute_asset: AssetId,
+            tribute_offered: T::Balance,
+            payment_asset: AssetId,
+            payment_requested: T::Balance,
+            shares_requested: u64,
+            loot_requested: u64,
+            detail: Vec<u8>
+        ) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SUBMIT_DRAFT)?;
+            Self::ensure_storage_migrated()?;
 
-            // create proposal
-            let access_proposal = AccessProposal {
-                proposer: sender.clone(),
-                applicant: applicant.clone(),
-                energies_requested: energies_requested,
-                mortgage: Self::proposal_mortgage(),
-                deposit: deposit,
-                starting_period: this_starting_period,
-                yes_votes: 0,
-                no_votes: 0,
-                processed: false,
-                did_pass: false,
-                aborted: false,
+            ensure!(Self::dao_exists(dao_id), DaoError::DaoNotFound.into());
+            ensure!(Self::token_whitelist((dao_id, tribute_asset)), DaoError::TributeAssetNotWhitelisted.into());
+            ensure!(Self::token_whitelist((dao_id, payment_asset)), DaoError::PaymentAssetNotWhitelisted.into());
+            let detail = Self::parse_content_id(&detail)?;
+
+            let access_drafts_count = Self::access_drafts_count(dao_id);
+            let new_access_drafts_count = access_drafts_count.checked_add(1).ok_or(DaoError::Overflow)?;
+
+            // the proposer, mortgage and starting period are set by the sponsor
+            let mut draft = AccessProposal {
+                applicant: sender.clone(),
+                energies_requested: shares_requested,
+                loot_requested: loot_requested,
+                deposit_asset: tribute_asset,
+                deposit: tribute_offered,
+                payment_asset: payment_asset,
+                payment_requested: payment_requested,
                 detail: detail,
+                ..Default::default()
             };
+            draft.storage_deposit = Self::storage_deposit_of(&draft)?;
 
-            if deposit > <T::Balance as As<u64>>::sa(0) {
-                let _ = <balances::Module<T> as Currency<_>>::withdraw(&applicant, deposit, WithdrawReason::Reserve, ExistenceRequirement::KeepAlive)?;
-                <DepositPool<T>>::put(new_deposit_pool);
-                <Allowance<T>>::mutate(applicant.clone(), |n| *n -= deposit);
+            // the tribute stays reserved by the applicant until the draft is sponsored or cancelled
+            if tribute_offered > <T::Balance as As<u64>>::sa(0) {
+                Self::reserve_asset(tribute_asset, &sender, tribute_offered)?;
             }
-            let _ = <balances::Module<T> as Currency<_>>::withdraw(&sender, access_proposal.mortgage, WithdrawReason::Reserve, ExistenceRequirement::KeepAlive)?;
-            <MortgagePool<T>>::put(new_mortgage_pool);
-            <TotalEnergiesRequested<T>>::put(new_total_energies_requested);
-            <AccessProposals<T>>::insert(Self::access_proposals_count(), access_proposal);
-            <AccessProposalsCount<T>>::mutate(|n| *n += 1);
+            if let Err(e) = <balances::Module<T> as ReservableCurrency<_>>::reserve(&sender, draft.storage_deposit) {
+                Self::unreserve_asset(tribute_asset, &sender, tribute_offered);
+                return Err(e);
+            }
+            <AccessDrafts<T>>::insert((dao_id, access_drafts_count), draft);
+            <AccessDraftsCount<T>>::insert(dao_id, new_access_drafts_count);
 
-            Self::deposit_event(RawEvent::SubmitAccessProposal(sender, applicant, energies_requested, deposit, this_starting_period));
+            Self::deposit_event(RawEvent::SubmitAccessDraft(dao_id, access_drafts_count, sender));
             Ok(())
         }
 
-        pub fn submit_project_proposal(
-            origin, 
-            applicant: T::AccountId,
+        pub fn submit_project_draft(
+            origin,
+            dao_id: DaoId,
+            grant_asset: AssetId,
             milestone_1_requested: T::Balance,
             milestone_2_requested: T::Balance,
             milestone_3_requested: T::Balance,
             detail: Vec<u8>
         ) -> Result {
             let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SUBMIT_DRAFT)?;
+            Self::ensure_storage_migrated()?;
 
-            ensure!(Self::is_member(&sender), "Sender is not a member");
-            ensure!(applicant != T::AccountId::default(), "Applicant is not set");
-
-            let mut this_starting_period: u64 = Self::get_current_period();
-            if Self::unprocssed_queue_length() != 0 && Self::project_proposals(Self::projects_process_queue(Self::unprocssed_queue_head() + Self::unprocssed_queue_length() - 1)).starting_period > this_starting_period {
-                this_starting_period = Self::project_proposals(Self::projects_process_queue(Self::unprocssed_queue_head() + Self::unprocssed_queue_length() - 1)).starting_period + 1;
-            }
+            ensure!(Self::dao_exists(dao_id), DaoError::DaoNotFound.into());
+            ensure!(Self::token_whitelist((dao_id, grant_asset)), DaoError::GrantAssetNotWhitelisted.into());
+            let detail = Self::parse_content_id(&detail)?;
 
-            let new_mortgage_pool = Self::mortgage_pool().checked_add(&Self::proposal_mortgage()).ok_or("overflow in calculating mortgage pool")?;
+            let project_drafts_count = Self::project_drafts_count(dao_id);
+            let new_project_drafts_count = project_drafts_count.checked_add(1).ok_or(DaoError::Overflow)?;
 
-            // create proposal
-            let project_proposal = ProjectProposal {
-                proposer: sender.clone(),
-                applicant: applicant.clone(),
-                mortgage: Self::proposal_mortgage(),
-                starting_period: this_starting_period,
+            // the proposer, mortgage and starting period are set by the sponsor
+            let mut draft = ProjectProposal {
+                applicant: sender.clone(),
+                grant_asset: grant_asset,
                 milestone_1_requested: milestone_1_requested,
                 milestone_2_requested: milestone_2_requested,
                 milestone_3_requested: milestone_3_requested,
-                yes_votes: 0,
-                no_votes: 0,
-                processed: false,
-                stage_did_pass: false,
-                round: 0,
-                aborted: false,
-                status: ProjectStatus::Initialization,
                 detail: detail,
+                ..Default::default()
             };
+            draft.storage_deposit = Self::storage_deposit_of(&draft)?;
+
+            <balances::Module<T> as ReservableCurrency<_>>::reserve(&sender, draft.storage_deposit)?;
+            <ProjectDrafts<T>>::insert((dao_id, project_drafts_count), draft);
+            <ProjectDraftsCount<T>>::insert(dao_id, new_project_drafts_count);
+
+            Self::deposit_event(RawEvent::SubmitProjectDraft(dao_id, project_drafts_count, sender));
+            Ok(())
+        }
+
+        pub fn sponsor_access_proposal(origin, dao_id: DaoId, draft_index: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SPONSOR)?;
+            Self::ensure_storage_migrated()?;
+
+            let draft = Self::access_drafts((dao_id, draft_index)).ok_or(DaoError::DraftNotFound)?;
+
+            Self::create_access_proposal(dao_id, sender.clone(), draft.applicant.clone(), draft.kind, draft.deposit_asset, draft.deposit, draft.payment_asset,
+                draft.payment_requested, draft.energies_requested, draft.loot_requested, draft.detail, true)?;
+            // the sponsor has reserved the storage deposit of the proposal
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&draft.applicant, draft.storage_deposit);
+            <AccessDrafts<T>>::remove((dao_id, draft_index));
 
-            let _ = <balances::Module<T> as Currency<_>>::withdraw(&sender, project_proposal.mortgage, WithdrawReason::Reserve, ExistenceRequirement::KeepAlive)?;
-            <MortgagePool<T>>::put(new_mortgage_pool);
-            <ProjectProposals<T>>::insert(Self::project_proposals_count(), project_proposal);
-            <ProjectsProcessQueue<T>>::insert(Self::unprocssed_queue_head() + Self::unprocssed_queue_length(), Self::project_proposals_count());
-            <UnprocessedQueueLength<T>>::mutate(|n| *n += 1);
-            <ProjectProposalsCount<T>>::mutate(|n| *n += 1);
+            Self::deposit_event(RawEvent::SponsorAccessProposal(dao_id, draft_index, sender, Self::access_proposals_count(dao_id) - 1));
+            Ok(())
+        }
+
+        pub fn sponsor_project_proposal(origin, dao_id: DaoId, draft_index: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SPONSOR)?;
+            Self::ensure_storage_migrated()?;
+
+            let draft = Self::project_drafts((dao_id, draft_index)).ok_or(DaoError::DraftNotFound)?;
+
+            Self::create_project_proposal(dao_id, sender.clone(), draft.applicant.clone(), draft.grant_asset, draft.milestone_1_requested, draft.milestone_2_requested,
+                draft.milestone_3_requested, draft.detail)?;
+            // the sponsor has reserved the storage deposit of the proposal
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&draft.applicant, draft.storage_deposit);
+            <ProjectDrafts<T>>::remove((dao_id, draft_index));
+
+            Self::deposit_event(RawEvent::SponsorProjectProposal(dao_id, draft_index, sender, Self::project_proposals_count(dao_id) - 1));
+            Ok(())
+        }
+
+        pub fn cancel_access_draft(origin, dao_id: DaoId, draft_index: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::CANCEL_DRAFT)?;
+            Self::ensure_storage_migrated()?;
+
+            let draft = Self::access_drafts((dao_id, draft_index)).ok_or(DaoError::DraftNotFound)?;
+            ensure!(sender == draft.applicant, DaoError::NotDraftApplicant.into());
+
+            if draft.deposit > <T::Balance as As<u64>>::sa(0) {
+                Self::unreserve_asset(draft.deposit_asset, &sender, draft.deposit);
+            }
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&sender, draft.storage_deposit);
+            <AccessDrafts<T>>::remove((dao_id, draft_index));
+
+            Self::deposit_event(RawEvent::CancelAccessDraft(dao_id, draft_index));
+            Ok(())
+        }
+
+        pub fn cancel_project_draft(origin, dao_id: DaoId, draft_index: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::CANCEL_DRAFT)?;
+            Self::ensure_storage_migrated()?;
+
+            let draft = Self::project_drafts((dao_id, draft_index)).ok_or(DaoError::DraftNotFound)?;
+            ensure!(sender == draft.applicant, DaoError::NotDraftApplicant.into());
 
-            Self::deposit_event(RawEvent::SubmitProjectProposal(sender, applicant, milestone_1_requested, milestone_2_requested, milestone_3_requested, this_starting_period));
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&sender, draft.storage_deposit);
+            <ProjectDrafts<T>>::remove((dao_id, draft_index));
+
+            Self::deposit_event(RawEvent::CancelProjectDraft(dao_id, draft_index));
             Ok(())
         }
 
-        pub fn forward_to_milestone(origin, project_proposal_index: u32) -> Result {
+        pub fn forward_to_milestone(origin, dao_id: DaoId, project_proposal_index: u32) -> Result {
             let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::FORWARD_TO_MILESTONE)?;
+            Self::ensure_storage_migrated()?;
 
-            ensure!(Self::is_member(&sender), "Sender is not a member");
-            ensure!(<ProjectProposals<T>>::exists(project_proposal_index), "This project proposal not exists!");
-            let mut project_proposal = Self::project_proposals(project_proposal_index);
+            ensure!(Self::is_member(dao_id, &sender), DaoError::NotMember.into());
+            ensure!(<ProjectProposals<T>>::exists((dao_id, project_proposal_index)), DaoError::InvalidProjectProposalIndex.into());
+            let mut project_proposal = Self::project_proposals((dao_id, project_proposal_index));
 
-            ensure!(project_proposal.processed, "Must forward project until be processed!");
-            ensure!(!project_proposal.aborted, "This project has been aborted");
+            ensure!(project_proposal.processed, DaoError::ProjectNotProcessed.into());
+            ensure!(!project_proposal.aborted, DaoError::ProjectProposalAborted.into());
 
             if project_proposal.stage_did_pass {
                 match project_proposal.status {
-                    ProjectStatus::Initialization => { 
+                    ProjectStatus::Initialization => {
                             project_proposal.status = ProjectStatus::Milestone1;
                         },
-                    ProjectStatus::Milestone1 => { 
+                    ProjectStatus::Milestone1 => {
                             project_proposal.status = ProjectStatus::Milestone2;
                         },
-                    ProjectStatus::Milestone2 => { 
+                    ProjectStatus::Milestone2 => {
                             project_proposal.status = ProjectStatus::Milestone3;
                         },
-                    ProjectStatus::Milestone3 => { return Err("This project is completely done!") },
+                    ProjectStatus::Milestone3 => { return Err(DaoError::ProjectCompleted.into()) },
                 }
                 project_proposal.round = 0;
             }else {
-                project_proposal.round += 1;
+                project_proposal.round = project_proposal.round.checked_add(1).ok_or(DaoError::Overflow)?;
             }
 
-            let grant_locked = match project_proposal.status {
-                ProjectStatus::Milestone1 => project_proposal.milestone_1_requested,
-                ProjectStatus::Milestone2 => project_proposal.milestone_2_requested,
-                ProjectStatus::Milestone3 => project_proposal.milestone_3_requested,
-                _ => <T::Balance as As<u64>>::sa(0),
-            };
+            let grant_locked = Self::stage_grant(&project_proposal);
             project_proposal.stage_did_pass = false;
             project_proposal.processed = false;
             project_proposal.yes_votes = 0;
             project_proposal.no_votes = 0;
-            
-            let mut this_starting_period: u64 = Self::get_current_period();
-            if Self::unprocssed_queue_length() != 0 && Self::project_proposals(Self::projects_process_queue(Self::unprocssed_queue_head() + Self::unprocssed_queue_length() - 1)).starting_period > this_starting_period {
-                this_starting_period = Self::project_proposals(Self::projects_process_queue(Self::unprocssed_queue_head() + Self::unprocssed_queue_length() - 1)).starting_period + 1;
-            }
+
+            let this_starting_period = Self::next_project_starting_period(dao_id);
             project_proposal.starting_period = this_starting_period;
+            let unprocessed_queue_length = Self::unprocssed_queue_length(dao_id);
+            let new_unprocessed_queue_length = unprocessed_queue_length.checked_add(1).ok_or(DaoError::Overflow)?;
 
             if grant_locked > <T::Balance as As<u64>>::sa(0) {
-                ensure!(Self::free_pool() >= grant_locked, "Free pool is insufficient!");
-                <FreePool<T>>::mutate(|n| *n -= grant_locked);
-                <GrantLockedPool<T>>::mutate(|n| *n += grant_locked);
+                let grant_key = (dao_id, project_proposal.grant_asset);
+                let new_free_pool = Self::free_pool(grant_key).checked_sub(&grant_locked).ok_or(DaoError::FreePoolInsufficient)?;
+                let new_grant_locked_pool = Self::grant_locked_pool(grant_key).checked_add(&grant_locked).ok_or(DaoError::Overflow)?;
+                <FreePool<T>>::insert(grant_key, new_free_pool);
+                <GrantLockedPool<T>>::insert(grant_key, new_grant_locked_pool);
             }
-            <ProjectProposals<T>>::insert(project_proposal_index, project_proposal.clone());
-            <ProjectsProcessQueue<T>>::insert(Self::unprocssed_queue_head() + Self::unprocssed_queue_length(), project_proposal_index);
-            <UnprocessedQueueLength<T>>::mutate(|n| *n += 1);
+            <ProjectProposals<T>>::insert((dao_id, project_proposal_index), project_proposal.clone());
+            <ProjectsProcessQueue<T>>::insert((dao_id, Self::unprocssed_queue_head(dao_id) + unprocessed_queue_length), project_proposal_index);
+            <UnprocessedQueueLength<T>>::insert(dao_id, new_unprocessed_queue_length);
+            Self::schedule_dao(dao_id);
+            Self::list_insert(dao_id, ProposalList::OpenProject(project_proposal.status), (true, project_proposal_index));
 
-            Self::deposit_event(RawEvent::ForwardToMilestone(sender, project_proposal_index, project_proposal.status, project_proposal.round, this_starting_period));
+            Self::deposit_event(RawEvent::ForwardToMilestone(dao_id, sender, project_proposal_index, project_proposal.status, project_proposal.round, this_starting_period));
             Ok(())
         }
 
         pub fn submit_access_vote(
-            origin, 
-            access_proposal_index: u32, 
+            origin,
+            dao_id: DaoId,
+            access_proposal_index: u32,
             vote: bool
         ) -> Result {
             let sender = ensure_signed(origin)?;
+            // members vote without the weight fee, anyone else pays it before being rejected
+            if !Self::is_member(dao_id, &sender) {
+                Self::charge_weight(&sender, weight::VOTE)?;
+            }
+            Self::ensure_storage_migrated()?;
 
-            ensure!(<AccessProposals<T>>::exists(access_proposal_index), "access proposal index is invalid!");
-            let mut access_proposal = Self::access_proposals(access_proposal_index);
-            ensure!(Self::in_vote_period(access_proposal.starting_period), "Not in voting period!");
-            ensure!(!access_proposal.aborted, "The access proposal has been aborted!");
-            ensure!(Self::is_member(&sender), "Sender is not a member");
-            ensure!(!<VotesForAccess<T>>::exists((access_proposal_index, sender.clone())), "already voted!");
+            ensure!(<AccessProposals<T>>::exists((dao_id, access_proposal_index)), DaoError::InvalidAccessProposalIndex.into());
+            let mut access_proposal = Self::access_proposals((dao_id, access_proposal_index));
+            ensure!(Self::in_vote_period(dao_id, access_proposal.starting_period), DaoError::NotInVotingPeriod.into());
+            ensure!(!access_proposal.aborted, DaoError::AccessProposalAborted.into());
+            ensure!(Self::is_member(dao_id, &sender), DaoError::NotMember.into());
+            ensure!(!<VotesForAccess<T>>::exists((dao_id, access_proposal_index, sender.clone())), DaoError::AlreadyVoted.into());
 
-            let mut member = Self::members(sender.clone());
+            let (mut member, restored) = Self::with_restored_energy(dao_id, &sender)?;
+            let new_total_energies = Self::total_energies(dao_id).checked_add(restored).ok_or(DaoError::Overflow)?;
+            let vote_weight = Self::vote_weight(dao_id, &sender, member.energy)?;
 
             if vote {
-                access_proposal.yes_votes += member.energy;
+                access_proposal.yes_votes = access_proposal.yes_votes.checked_add(vote_weight).ok_or(DaoError::Overflow)?;
             }else {
-                access_proposal.no_votes += member.energy;
+                access_proposal.no_votes = access_proposal.no_votes.checked_add(vote_weight).ok_or(DaoError::Overflow)?;
             }
 
             if vote && access_proposal_index > member.highest_index_yes_vote {
                 member.highest_index_yes_vote = access_proposal_index;
-                <Members<T>>::insert(sender.clone(), member);
+                <Members<T>>::insert((dao_id, sender.clone()), member);
+            } else if restored > 0 {
+                <Members<T>>::insert((dao_id, sender.clone()), member);
             }
-            <AccessProposals<T>>::insert(access_proposal_index, access_proposal);
-            <VotesForAccess<T>>::insert((access_proposal_index, sender.clone()), vote);
+            Self::note_activity(dao_id, &sender, restored, new_total_energies);
+            let starting_period = access_proposal.starting_period;
+            <AccessProposals<T>>::insert((dao_id, access_proposal_index), access_proposal);
+            <VotesForAccess<T>>::insert((dao_id, access_proposal_index, sender.clone()), vote);
+            let voters_count = Self::access_voters_count((dao_id, access_proposal_index));
+            <AccessVoters<T>>::insert((dao_id, access_proposal_index, voters_count), sender.clone());
+            <AccessVotersCount<T>>::insert((dao_id, access_proposal_index), voters_count.saturating_add(1));
+            Self::note_vote(dao_id, &sender, false, access_proposal_index);
+            Self::lock_vote(dao_id, &sender, starting_period);
+            Self::refund_vote_fee(&sender);
 
-            Self::deposit_event(RawEvent::AccessVote(sender, access_proposal_index, vote));
+            Self::deposit_event(RawEvent::AccessVote(dao_id, sender, access_proposal_index, vote));
             Ok(())
         }
 
         pub fn submit_project_vote(
             origin,
+            dao_id: DaoId,
             project_proposal_index: u32,
             vote: bool
         ) -> Result {
             let sender = ensure_signed(origin)?;
+            // members vote without the weight fee, anyone else pays it before being rejected
+            if !Self::is_member(dao_id, &sender) {
+                Self::charge_weight(&sender, weight::VOTE)?;
+            }
+            Self::ensure_storage_migrated()?;
 
-            ensure!(<ProjectProposals<T>>::exists(project_proposal_index), "project proposal index is invalid!");
-            let mut project_proposal = Self::project_proposals(project_proposal_index);
-            ensure!(Self::in_vote_period(project_proposal.starting_period), "Not in voting period!");
-            ensure!(!project_proposal.aborted, "The project proposal has been aborted!");
-            ensure!(Self::is_member(&sender), "Sender is not a member");
-            ensure!(!<VotesForProject<T>>::exists((project_proposal_index, sender.clone(), project_proposal.status, project_proposal.round)), "already voted for this round!");
+            ensure!(<ProjectProposals<T>>::exists((dao_id, project_proposal_index)), DaoError::InvalidProjectProposalIndex.into());
+            let mut project_proposal = Self::project_proposals((dao_id, project_proposal_index));
+            ensure!(Self::in_vote_period(dao_id, project_proposal.starting_period), DaoError::NotInVotingPeriod.into());
+            ensure!(!project_proposal.aborted, DaoError::ProjectProposalAborted.into());
+            ensure!(Self::is_member(dao_id, &sender), DaoError::NotMember.into());
+            ensure!(!<VotesForProject<T>>::exists((dao_id, project_proposal_index, sender.clone(), project_proposal.status, project_proposal.round)), DaoError::AlreadyVoted.into());
 
-            let member = Self::members(sender.clone());
+            let (member, restored) = Self::with_restored_energy(dao_id, &sender)?;
+            let new_total_energies = Self::total_energies(dao_id).checked_add(restored).ok_or(DaoError::Overflow)?;
+            let vote_weight = Self::vote_weight(dao_id, &sender, member.energy)?;
             if vote {
-                project_proposal.yes_votes += member.energy;
+                project_proposal.yes_votes = project_proposal.yes_votes.checked_add(vote_weight).ok_or(DaoError::Overflow)?;
             }else {
-                project_proposal.no_votes += member.energy;
+                project_proposal.no_votes = project_proposal.no_votes.checked_add(vote_weight).ok_or(DaoError::Overflow)?;
+            }
+
+            if restored > 0 {
+                <Members<T>>::insert((dao_id, sender.clone()), member);
             }
-            
-            <ProjectProposals<T>>::insert(project_proposal_index, project_proposal.clone());
-            <VotesForProject<T>>::insert((project_proposal_index, sender.clone(), project_proposal.status.clone(), project_proposal.round), vote);
+            Self::note_activity(dao_id, &sender, restored, new_total_energies);
+            <ProjectProposals<T>>::insert((dao_id, project_proposal_index), project_proposal.clone());
+            <VotesForProject<T>>::insert((dao_id, project_proposal_index, sender.clone(), project_proposal.status.clone(), project_proposal.round), vote);
+            let voters_count = Self::project_voters_count((dao_id, project_proposal_index));
+            <ProjectVoters<T>>::insert((dao_id, project_proposal_index, voters_count), (sender.clone(), project_proposal.status, project_proposal.round));
+            <ProjectVotersCount<T>>::insert((dao_id, project_proposal_index), voters_count.saturating_add(1));
+            Self::note_vote(dao_id, &sender, true, project_proposal_index);
+            Self::lock_vote(dao_id, &sender, project_proposal.starting_period);
+            Self::refund_vote_fee(&sender);
 
-            Self::deposit_event(RawEvent::ProjectVote(sender, project_proposal_index, project_proposal.status.clone(), project_proposal.round, vote));
+            Self::deposit_event(RawEvent::ProjectVote(dao_id, sender, project_proposal_index, project_proposal.status.clone(), project_proposal.round, vote));
             Ok(())
         }
 
-        pub fn abort_access(origin, access_proposal_index: u32) -> Result {
+        pub fn abort_access(origin, dao_id: DaoId, access_proposal_index: u32) -> Result {
             let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::ABORT)?;
+            Self::ensure_storage_migrated()?;
 
-            ensure!(<AccessProposals<T>>::exists(access_proposal_index), "access proposal index is invalid!");
-            let mut access_proposal = Self::access_proposals(access_proposal_index);
-            ensure!(sender == access_proposal.applicant, "You are not the applicant of this access proposal!");
-            ensure!(!access_proposal.aborted, "This access proposal has been aborted!");
-            ensure!(Self::get_current_period() < (access_proposal.starting_period + Self::abort_window()), "Abort window has passed!");
+            ensure!(<AccessProposals<T>>::exists((dao_id, access_proposal_index)), DaoError::InvalidAccessProposalIndex.into());
+            let mut access_proposal = Self::access_proposals((dao_id, access_proposal_index));
+            ensure!(sender == access_proposal.applicant, DaoError::NotProposalApplicant.into());
+            ensure!(!access_proposal.aborted, DaoError::AccessProposalAborted.into());
+            ensure!(Self::get_current_period(dao_id) < access_proposal.starting_period.saturating_add(Self::abort_window(dao_id)), DaoError::AbortWindowPassed.into());
 
             access_proposal.aborted = true;
             let deposit_return = access_proposal.deposit;
             access_proposal.deposit = <T::Balance as As<u64>>::sa(0);
 
             if deposit_return > <T::Balance as As<u64>>::sa(0) {
-                let new_deposit_pool = Self::deposit_pool().checked_sub(&deposit_return).ok_or("overflow in calculating deposit return")?;
-                let _ = <balances::Module<T> as Currency<_>>::deposit_into_existing(&sender, deposit_return)
-                    .expect("`sender` must exist since a transaction is being made and withdraw will keep alive; qed.");
-                <DepositPool<T>>::put(new_deposit_pool);
+                let deposit_key = (dao_id, access_proposal.deposit_asset);
+                let new_deposit_pool = Self::deposit_pool(deposit_key).checked_sub(&deposit_return).ok_or(DaoError::Overflow)?;
+                Self::deposit_asset(access_proposal.deposit_asset, &sender, deposit_return);
+                <DepositPool<T>>::insert(deposit_key, new_deposit_pool);
             }
-            <AccessProposals<T>>::insert(access_proposal_index, access_proposal);
+            <AccessProposals<T>>::insert((dao_id, access_proposal_index), access_proposal);
 
-            Self::deposit_event(RawEvent::AccessAbort(access_proposal_index));
+            Self::deposit_event(RawEvent::AccessAbort(dao_id, access_proposal_index));
             Ok(())
         }
 
-        pub fn rage_quit(origin, energies_to_burn: u64) -> Result {
+        pub fn rage_quit(origin, dao_id: DaoId, energies_to_burn: u64, loot_to_burn: u64) -> Result {
             let sender = ensure_signed(origin)?;
+            let assets_weight = weight::RAGE_QUIT_PER_ASSET.checked_mul(Self::treasury_assets(dao_id).len() as u64).ok_or(DaoError::Overflow)?;
+            Self::charge_weight(&sender, weight::RAGE_QUIT + assets_weight)?;
+            Self::ensure_storage_migrated()?;
 
-            ensure!(Self::is_member(&sender), "Sender must be member!");
-            ensure!(energies_to_burn > 0, "energies to burn must more than 0");
-            let mut member = Self::members(sender.clone());
-            ensure!(member.energy >= energies_to_burn, "Energy is not enough");
-            ensure!(Self::access_proposals(member.highest_index_yes_vote).processed, "cant ragequit until highest index proposal member voted YES on is processed");
-
-            member.energy = member.energy.checked_sub(energies_to_burn).ok_or("overflow in calculating energy")?;
-            let new_total_energies = Self::total_energies() - energies_to_burn;
-            let redeem_balance = Self::free_pool().checked_mul(&<T::Balance as As<u64>>::sa(energies_to_burn)).ok_or("overflow in calculating redeem")?
-                                    .checked_div(&<T::Balance as As<u64>>::sa(Self::total_energies())).ok_or("overflow in calculating redeem")?;
-            let new_free_pool = Self::free_pool().checked_sub(&redeem_balance).ok_or("overflow in calculating free pool")?;
-
-            if redeem_balance > <T::Balance as As<u64>>::sa(0) {
-                let _ = <balances::Module<T> as Currency<_>>::deposit_into_existing(&sender, redeem_balance)
-                    .expect("`sender` must exist since a transaction is being made and withdraw will keep alive; qed.");
-                <FreePool<T>>::put(new_free_pool);
-            }
-            <Members<T>>::insert(sender.clone(), member);
-            <TotalEnergies<T>>::put(new_total_energies);
-            
-            Self::deposit_event(RawEvent::RageQuit(sender, energies_to_burn, redeem_balance));
-            Ok(())
-        }
+            // loot holders without energy are not members but can still quit
+            ensure!(<Members<T>>::exists((dao_id, sender.clone())), DaoError::NotShareholder.into());
+            let shares_to_burn = energies_to_burn.checked_add(loot_to_burn).ok_or(DaoError::Overflow)?;
+            ensure!(shares_to_burn > 0, DaoError::NothingToBurn.into());
+            let mut member = Self::members((dao_id, sender.clone()));
+            ensure!(member.energy >= energies_to_burn, DaoError::EnergyTooLow.into());
+            ensure!(member.loot >= loot_to_burn, DaoError::LootTooLow.into());
+            ensure!(Self::is_access_processed(dao_id, member.highest_index_yes_vote), DaoError::YesVoteNotProcessed.into());
 
-        fn on_initialize() {
-            let current_period = Self::get_current_period();
-
-
-            // process access proposal
-            let processed_access_proposals_count = Self::processed_access_proposals_count();
-            if Self::access_proposals_count() > processed_access_proposals_count{
-                // judge to process access proposal
-                let mut first_unprocessed_access_proposal = Self::access_proposals(processed_access_proposals_count);
-                if current_period >= first_unprocessed_access_proposal.starting_period + Self::voting_period_length() {
-                    // process the first_unprocessed_access_proposal
-                    first_unprocessed_access_proposal.processed = true;
-                    first_unprocessed_access_proposal.did_pass = (first_unprocessed_access_proposal.yes_votes > first_unprocessed_access_proposal.no_votes)
-                                                        && !first_unprocessed_access_proposal.aborted;
-
-                    <TotalEnergiesRequested<T>>::mutate(|n| *n -= first_unprocessed_access_proposal.energies_requested);
-
-                    if first_unprocessed_access_proposal.did_pass {
-                        
-                        if <Members<T>>::exists(first_unprocessed_access_proposal.applicant.clone()) {
-                            // member already exists
-                            let mut member = Self::members(first_unprocessed_access_proposal.applicant.clone());
-                            member.energy += first_unprocessed_access_proposal.energies_requested;
-
-                            <Members<T>>::insert(first_unprocessed_access_proposal.applicant.clone(), member.clone());
-                            // mint new energies
-                            <TotalEnergies<T>>::mutate(|n| *n += first_unprocessed_access_proposal.energies_requested);
-                        } else {
-                            // the applicant is a new member, create a new record
-                            let member = Member {
-                                energy: first_unprocessed_access_proposal.energies_requested,
-                                highest_index_yes_vote: 0,
-                            };
-
-                            <TotalEnergies<T>>::mutate(|n| *n += first_unprocessed_access_proposal.energies_requested);
-                            
-                            <Members<T>>::insert(first_unprocessed_access_proposal.applicant.clone(), member);
-                            <MembersArray<T>>::insert(Self::members_count(), first_unprocessed_access_proposal.applicant.clone());
-                            <MembersCount<T>>::mutate(|n| *n += 1);
-
-                            Self::deposit_event(RawEvent::NewMember(first_unprocessed_access_proposal.applicant.clone(), first_unprocessed_access_proposal.energies_requested));
-                        }
-
-                        if first_unprocessed_access_proposal.deposit > <T::Balance as As<u64>>::sa(0) {
-                            // move deposit from deposit pool to free balance
-                            <DepositPool<T>>::mutate(|n| *n -= first_unprocessed_access_proposal.deposit);
-                            <FreePool<T>>::mutate(|n| *n += first_unprocessed_access_proposal.deposit);
-                        }
+            member.energy = member.energy.checked_sub(energies_to_burn).ok_or(DaoError::Overflow)?;
+            member.loot = member.loot.checked_sub(loot_to_burn).ok_or(DaoError::Overflow)?;
+            let new_total_energies = Self::total_energies(dao_id).checked_sub(energies_to_burn).ok_or(DaoError::Overflow)?;
+            let new_total_loot = Self::total_loot(dao_id).checked_sub(loot_to_burn).ok_or(DaoError::Overflow)?;
+            let total_shares = Self::total_energies(dao_id).checked_add(Self::total_loot(dao_id)).ok_or(DaoError::Overflow)?;
 
-                    } else {
-                        // return deposit to applicant
-                        if first_unprocessed_access_proposal.deposit > <T::Balance as As<u64>>::sa(0) {
-                            <DepositPool<T>>::mutate(|n| *n -= first_unprocessed_access_proposal.deposit);
-                            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&first_unprocessed_access_proposal.applicant.clone(), first_unprocessed_access_proposal.deposit);
-                        }
-                    }
+            // every asset of the treasury is redeemed pro rata
+            let mut redeems = Vec::new();
+            for asset_id in Self::treasury_assets(dao_id) {
+                let free_pool = Self::free_pool((dao_id, asset_id));
+                let redeem_balance = free_pool.checked_mul(&<T::Balance as As<u64>>::sa(shares_to_burn)).ok_or(DaoError::Overflow)?
+                                        .checked_div(&<T::Balance as As<u64>>::sa(total_shares)).ok_or(DaoError::Overflow)?;
+                let new_free_pool = free_pool.checked_sub(&redeem_balance).ok_or(DaoError::Overflow)?;
+                if redeem_balance > <T::Balance as As<u64>>::sa(0) {
+                    redeems.push((asset_id, redeem_balance, new_free_pool));
+                }
+            }
+
+            for (asset_id, redeem_balance, new_free_pool) in redeems {
+                Self::deposit_asset(asset_id, &sender, redeem_balance);
+                <FreePool<T>>::insert((dao_id, asset_id), new_free_pool);
+                Self::deposit_event(RawEvent::Redeem(dao_id, sender.clone(), asset_id, redeem_balance));
+            }
+            Self::sync_active_member(dao_id, &sender, member.energy);
+            if member.energy == 0 {
+                // energy lost to inactivity is only given back to voters
+                <DecayedEnergy<T>>::remove((dao_id, sender.clone()));
+                <DecayedPeriods<T>>::remove((dao_id, sender.clone()));
+            }
+            <Members<T>>::insert((dao_id, sender.clone()), member);
+            <TotalEnergies<T>>::insert(dao_id, new_total_energies);
+            <TotalLoot<T>>::insert(dao_id, new_total_loot);
+
+            Self::deposit_event(RawEvent::RageQuit(dao_id, sender, energies_to_burn, loot_to_burn));
+            Ok(())
+        }
+
+        pub fn transfer_energy(origin, dao_id: DaoId, to: T::AccountId, energies: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::TRANSFER_ENERGY)?;
+            Self::ensure_storage_migrated()?;
+
+            ensure!(Self::energy_transferable(dao_id), DaoError::EnergyNotTransferable.into());
+            ensure!(Self::is_member(dao_id, &sender), DaoError::NotMember.into());
+            ensure!(Self::is_member(dao_id, &to), DaoError::ReceiverNotMember.into());
+            ensure!(sender != to, DaoError::TransferToSelf.into());
+            ensure!(energies > 0, DaoError::NothingToTransfer.into());
+
+            let mut from_member = Self::members((dao_id, sender.clone()));
+            let mut to_member = Self::members((dao_id, to.clone()));
+            ensure!(from_member.energy >= energies, DaoError::EnergyTooLow.into());
+            // the same guards as rage quit, and the energy must not be counted twice in an open vote
+            ensure!(Self::is_access_processed(dao_id, from_member.highest_index_yes_vote), DaoError::YesVoteNotProcessed.into());
+            ensure!(Self::get_current_period(dao_id) >= Self::vote_locked_until((dao_id, sender.clone())), DaoError::VotesNotClosed.into());
+
+            from_member.energy = from_member.energy.checked_sub(energies).ok_or(DaoError::Overflow)?;
+            to_member.energy = to_member.energy.checked_add(energies).ok_or(DaoError::Overflow)?;
+
+            Self::sync_active_member(dao_id, &sender, from_member.energy);
+            if from_member.energy == 0 {
+                // energy lost to inactivity is only given back to voters
+                <DecayedEnergy<T>>::remove((dao_id, sender.clone()));
+                <DecayedPeriods<T>>::remove((dao_id, sender.clone()));
+            }
+            <Members<T>>::insert((dao_id, sender.clone()), from_member);
+            <Members<T>>::insert((dao_id, to.clone()), to_member);
+
+            Self::deposit_event(RawEvent::TransferEnergy(dao_id, sender, to, energies));
+            Ok(())
+        }
+
+        pub fn reap_proposal(origin, dao_id: DaoId, is_project: bool, index: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            let voters = if is_project { Self::project_voters_count((dao_id, index)) } else { Self::access_voters_count((dao_id, index)) };
+            let voters_weight = weight::REAP_PER_VOTER.checked_mul(voters as u64).ok_or(DaoError::Overflow)?;
+            Self::charge_weight(&sender, weight::REAP + voters_weight)?;
+            Self::ensure_storage_migrated()?;
+
+            let retention_ends = |starting_period: u64| starting_period.saturating_add(Self::voting_period_length(dao_id)).saturating_add(Self::retention_periods());
+
+            let (proposer, storage_deposit) = if is_project {
+                ensure!(<ProjectProposals<T>>::exists((dao_id, index)), DaoError::InvalidProjectProposalIndex.into());
+                let project_proposal = Self::project_proposals((dao_id, index));
+                // a project is settled once its last milestone passed or it was cancelled
+                ensure!(project_proposal.processed && (project_proposal.completed || project_proposal.aborted), DaoError::ProposalNotSettled.into());
+                ensure!(Self::pending_credit((dao_id, true)).map_or(true, |credit| credit.index != index), DaoError::ProposalNotSettled.into());
+                ensure!(Self::get_current_period(dao_id) >= retention_ends(project_proposal.starting_period), DaoError::RetentionNotPassed.into());
+
+                <ProjectVotersCredited<T>>::remove((dao_id, index));
+                for position in 0..<ProjectVotersCount<T>>::take((dao_id, index)) {
+                    let (voter, status, round) = <ProjectVoters<T>>::take((dao_id, index, position));
+                    Self::list_remove(dao_id, ProposalList::VotedBy(voter.clone()), (true, index));
+                    <VotesForProject<T>>::remove((dao_id, index, voter, status, round));
+                }
+                Self::unindex_proposal(dao_id, true, index, &project_proposal.proposer, &project_proposal.applicant);
+                <ProjectProposals<T>>::remove((dao_id, index));
+                <ProjectProposalsArchive<T>>::insert((dao_id, index), ProposalRecord {
+                    did_pass: project_proposal.stage_did_pass,
+                    yes_votes: project_proposal.yes_votes,
+                    no_votes: project_proposal.no_votes,
+                });
+                (project_proposal.proposer, project_proposal.storage_deposit)
+            } else {
+                ensure!(<AccessProposals<T>>::exists((dao_id, index)), DaoError::InvalidAccessProposalIndex.into());
+                let access_proposal = Self::access_proposals((dao_id, index));
+                ensure!(access_proposal.processed, DaoError::ProposalNotSettled.into());
+                ensure!(Self::pending_credit((dao_id, false)).map_or(true, |credit| credit.index != index), DaoError::ProposalNotSettled.into());
+                ensure!(Self::get_current_period(dao_id) >= retention_ends(access_proposal.starting_period), DaoError::RetentionNotPassed.into());
+
+                for position in 0..<AccessVotersCount<T>>::take((dao_id, index)) {
+                    let voter = <AccessVoters<T>>::take((dao_id, index, position));
+                    Self::list_remove(dao_id, ProposalList::VotedBy(voter.clone()), (false, index));
+                    <VotesForAccess<T>>::remove((dao_id, index, voter));
+                }
+                Self::unindex_proposal(dao_id, false, index, &access_proposal.proposer, &access_proposal.applicant);
+                <AccessProposals<T>>::remove((dao_id, index));
+                <AccessProposalsArchive<T>>::insert((dao_id, index), ProposalRecord {
+                    did_pass: access_proposal.did_pass,
+                    yes_votes: access_proposal.yes_votes,
+                    no_votes: access_proposal.no_votes,
+                });
+                (access_proposal.proposer, access_proposal.storage_deposit)
+            };
+
+            // the reaper is rewarded out of the storage deposit, the rest goes back to the proposer
+            let reward = if Self::reap_reward() < storage_deposit { Self::reap_reward() } else { storage_deposit };
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::slash_reserved(&proposer, reward);
+            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&sender, reward);
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&proposer, storage_deposit - reward);
+
+            Self::deposit_event(RawEvent::ReapProposal(dao_id, is_project, index, sender, reward));
+            Ok(())
+        }
+
+        pub fn set_profile(
+            origin,
+            dao_id: DaoId,
+            display_name: Vec<u8>,
+            contact: Vec<u8>,
+            avatar: Option<Vec<u8>>,
+            public_key: Option<[u8; 32]>
+        ) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::SET_PROFILE)?;
+            Self::ensure_storage_migrated()?;
+
+            // loot holders are shareholders too
+            ensure!(<Members<T>>::exists((dao_id, sender.clone())), DaoError::NotShareholder.into());
+            let avatar = match avatar {
+                Some(raw) => Some(Self::parse_content_id(&raw)?),
+                None => None,
+            };
+            let profile = Profile {
+                display_name: display_name,
+                contact: contact,
+                avatar: avatar,
+                public_key: public_key,
+            };
+            ensure!(profile.encode().len() <= Self::max_profile_length() as usize, DaoError::ProfileTooLong.into());
+
+            // only the difference with the deposit of the previous profile moves
+            let key = (dao_id, sender.clone());
+            let old_deposit = Self::profile_deposit(key.clone());
+            let new_deposit = Self::storage_deposit_of(&profile)?;
+            if new_deposit > old_deposit {
+                <balances::Module<T> as ReservableCurrency<_>>::reserve(&sender, new_deposit - old_deposit)?;
+            } else {
+                let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&sender, old_deposit - new_deposit);
+            }
+            <Profiles<T>>::insert(key.clone(), profile);
+            <ProfileDeposit<T>>::insert(key, new_deposit);
+
+            Self::deposit_event(RawEvent::ProfileSet(dao_id, sender, new_deposit));
+            Ok(())
+        }
+
+        pub fn clear_profile(origin, dao_id: DaoId) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::CLEAR_PROFILE)?;
+            Self::ensure_storage_migrated()?;
+
+            let key = (dao_id, sender.clone());
+            ensure!(<Profiles<T>>::exists(key.clone()), DaoError::NoProfile.into());
 
-                    // update access proposal
-                    let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&first_unprocessed_access_proposal.proposer, first_unprocessed_access_proposal.mortgage);
-                    <MortgagePool<T>>::mutate(|n| *n -= first_unprocessed_access_proposal.mortgage);
-                    <AccessProposals<T>>::insert(processed_access_proposals_count, first_unprocessed_access_proposal.clone());
-                    <ProcessedAccessProposalsCount<T>>::mutate(|n| *n += 1);
-                
-                    Self::deposit_event(RawEvent::ProcessAccessProposal(processed_access_proposals_count, first_unprocessed_access_proposal.proposer, first_unprocessed_access_proposal.applicant.clone(), 
-                    first_unprocessed_access_proposal.deposit, first_unprocessed_access_proposal.energies_requested, first_unprocessed_access_proposal.did_pass));
+            <Profiles<T>>::remove(key.clone());
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(&sender, <ProfileDeposit<T>>::take(key));
+
+            Self::deposit_event(RawEvent::ProfileCleared(dao_id, sender));
+            Ok(())
+        }
+
+        pub fn claim_vote_rewards(origin, dao_id: DaoId) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::dao_exists(dao_id), DaoError::DaoNotFound.into());
+            let periods = Self::vote_reward_periods((dao_id, sender.clone()));
+            let periods_weight = weight::CLAIM_VOTE_REWARDS_PER_PERIOD.checked_mul(periods.len() as u64).ok_or(DaoError::Overflow)?;
+            Self::charge_weight(&sender, weight::CLAIM_VOTE_REWARDS + periods_weight)?;
+            Self::ensure_storage_migrated()?;
+
+            // points are credited while their period runs, so it is shared once it is over
+            let current_period = Self::get_current_period(dao_id);
+            let (ended, pending): (Vec<u64>, Vec<u64>) = periods.into_iter().partition(|period| *period < current_period);
+            ensure!(!ended.is_empty(), DaoError::NoVoteRewards.into());
+
+            let zero = <T::Balance as As<u64>>::sa(0);
+            let native_key = (dao_id, NATIVE_ASSET_ID);
+            let mut free_pool = Self::free_pool(native_key);
+            let mut reward_pool = Self::vote_reward_pool(dao_id);
+            let mut total_reward = zero;
+            let mut settled = Vec::new();
+            for period in ended {
+                let points = Self::vote_points((dao_id, period, sender.clone()));
+                // the budget of a period leaves the free pool on its first claim
+                let (remaining, points_left) = match Self::period_vote_rewards((dao_id, period)) {
+                    Some(rewards) => rewards,
+                    None => {
+                        let budget = if Self::vote_reward_budget() < free_pool { Self::vote_reward_budget() } else { free_pool };
+                        free_pool = free_pool - budget;
+                        reward_pool = reward_pool.checked_add(&budget).ok_or(DaoError::Overflow)?;
+                        (budget, Self::period_vote_points((dao_id, period)))
+                    },
+                };
+                // shared over the points left, so the last claim takes the rounding
+                let reward = remaining.checked_mul(&<T::Balance as As<u64>>::sa(points as u64)).ok_or(DaoError::Overflow)?
+                                .checked_div(&<T::Balance as As<u64>>::sa(points_left as u64)).ok_or(DaoError::Overflow)?;
+                let new_points_left = points_left.checked_sub(points).ok_or(DaoError::Overflow)?;
+                reward_pool = reward_pool.checked_sub(&reward).ok_or(DaoError::Overflow)?;
+                total_reward = total_reward.checked_add(&reward).ok_or(DaoError::Overflow)?;
+                settled.push((period, remaining - reward, new_points_left));
+            }
+
+            <FreePool<T>>::insert(native_key, free_pool);
+            <VoteRewardPool<T>>::insert(dao_id, reward_pool);
+            for (period, remaining, points_left) in settled {
+                <VotePoints<T>>::remove((dao_id, period, sender.clone()));
+                if points_left == 0 {
+                    <PeriodVoteRewards<T>>::remove((dao_id, period));
+                    <PeriodVotePoints<T>>::remove((dao_id, period));
+                    <OpenRewardPeriods<T>>::mutate(dao_id, |open| open.retain(|p| *p != period));
+                } else {
+                    <PeriodVoteRewards<T>>::insert((dao_id, period), (remaining, points_left));
+                    <OpenRewardPeriods<T>>::mutate(dao_id, |open| push_unique(open, period));
                 }
             }
+            <VoteRewardPeriods<T>>::insert((dao_id, sender.clone()), pending);
+            if total_reward > zero {
+                Self::deposit_asset(NATIVE_ASSET_ID, &sender, total_reward);
+            }
+
+            Self::deposit_event(RawEvent::VoteRewardsClaimed(dao_id, sender, total_reward));
+            Ok(())
+        }
 
-            // ProjectsProcessQueue get(projects_process_queue): map u32 => u32;
-            // UnprocessedQueueHead get(unprocssed_queue_head): u32;
-            // UnprocessedQueueLength get(unprocssed_queue_length): u32;
+        pub fn claim_stream(origin, dao_id: DaoId, project_proposal_index: u32, stage: ProjectStatus) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::charge_weight(&sender, weight::CLAIM_STREAM)?;
+            Self::ensure_storage_migrated()?;
 
-            // process project proposal
-            let unprocessed_queue_length = Self::unprocssed_queue_length();
-            if unprocessed_queue_length > 0 {
-                let first_unprocessed_project_proposal_index = Self::projects_process_queue(Self::unprocssed_queue_head());
-                let mut first_unprocessed_project_proposal = Self::project_proposals(first_unprocessed_project_proposal_index);
+            let stream_key = (dao_id, project_proposal_index, stage);
+            let mut stream = Self::grant_streams(stream_key).ok_or(DaoError::NoGrantStream)?;
+            ensure!(sender == stream.recipient, DaoError::NotProposalApplicant.into());
 
-                if current_period >= first_unprocessed_project_proposal.starting_period + Self::voting_period_length() {
-                    // process the project
-                    first_unprocessed_project_proposal.processed = true;
-                    first_unprocessed_project_proposal.stage_did_pass = (first_unprocessed_project_proposal.yes_votes > first_unprocessed_project_proposal.no_votes)
-                                                        && !first_unprocessed_project_proposal.aborted;
+            let claimable = Self::vested(&stream, Self::get_current_period(dao_id))?.checked_sub(&stream.claimed).ok_or(DaoError::Overflow)?;
+            ensure!(claimable > <T::Balance as As<u64>>::sa(0), DaoError::NothingVested.into());
+            let pool_key = (dao_id, stream.asset);
+            let new_stream_locked_pool = Self::stream_locked_pool(pool_key).checked_sub(&claimable).ok_or(DaoError::Overflow)?;
+            stream.claimed = stream.claimed.checked_add(&claimable).ok_or(DaoError::Overflow)?;
 
-                    let grant_this_stage = match first_unprocessed_project_proposal.status {
-                        ProjectStatus::Initialization => <T::Balance as As<u64>>::sa(0),
-                        ProjectStatus::Milestone1 => first_unprocessed_project_proposal.milestone_1_requested,
-                        ProjectStatus::Milestone2 => first_unprocessed_project_proposal.milestone_2_requested,
-                        ProjectStatus::Milestone3 => first_unprocessed_project_proposal.milestone_3_requested,
-                    };
-                    if first_unprocessed_project_proposal.stage_did_pass {
-                        if first_unprocessed_project_proposal.status == ProjectStatus::Milestone3 {
-                            first_unprocessed_project_proposal.aborted = true;
-                            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&first_unprocessed_project_proposal.proposer, first_unprocessed_project_proposal.mortgage);
-                            <MortgagePool<T>>::mutate(|n| *n -= first_unprocessed_project_proposal.mortgage);
-                        }
-
-                        if grant_this_stage > <T::Balance as As<u64>>::sa(0) {
-                            <GrantLockedPool<T>>::mutate(|n| *n -= grant_this_stage);
-                            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&first_unprocessed_project_proposal.applicant.clone(), grant_this_stage);
-                        }
-                    }else {
-                        if grant_this_stage > <T::Balance as As<u64>>::sa(0) {
-                            <GrantLockedPool<T>>::mutate(|n| *n -= grant_this_stage);
-                            <FreePool<T>>::mutate(|n| *n += grant_this_stage);
-                        }
-                    }
+            Self::deposit_asset(stream.asset, &sender, claimable);
+            <StreamLockedPool<T>>::insert(pool_key, new_stream_locked_pool);
+            if stream.claimed == stream.total {
+                <GrantStreams<T>>::remove(stream_key);
+            } else {
+                <GrantStreams<T>>::insert(stream_key, stream);
+            }
+
+            Self::deposit_event(RawEvent::StreamClaimed(dao_id, project_proposal_index, stage, sender, claimable));
+            Ok(())
+        }
+
+        /// Stop a project between two stages. The vested part of its grant streams is paid out
+        /// and the rest goes back to the free pool.
+        pub fn cancel_project(origin, dao_id: DaoId, project_proposal_index: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+            let streams = Self::open_project_streams(dao_id, project_proposal_index);
+            let streams_weight = weight::CANCEL_PROJECT_PER_STREAM.checked_mul(streams.len() as u64).ok_or(DaoError::Overflow)?;
+            Self::charge_weight(&sender, weight::CANCEL_PROJECT + streams_weight)?;
+            Self::ensure_storage_migrated()?;
+
+            ensure!(<ProjectProposals<T>>::exists((dao_id, project_proposal_index)), DaoError::InvalidProjectProposalIndex.into());
+            let mut project_proposal = Self::project_proposals((dao_id, project_proposal_index));
+            ensure!(project_proposal.processed, DaoError::ProjectNotProcessed.into());
+            ensure!(!project_proposal.aborted, DaoError::ProjectProposalAborted.into());
+            ensure!(!project_proposal.completed, DaoError::ProjectCompleted.into());
+            // the applicant can give up, the members can drop a project whose stage failed
+            ensure!(sender == project_proposal.applicant || (Self::is_member(dao_id, &sender) && !project_proposal.stage_did_pass),
+                DaoError::ProjectNotCancellable.into());
+
+            // the mortgage goes back as for a completed project
+            let new_mortgage_pool = Self::mortgage_pool(dao_id).checked_sub(&project_proposal.mortgage).ok_or(DaoError::Overflow)?;
+            // every stream of a project is in its grant asset
+            let pool_key = (dao_id, project_proposal.grant_asset);
+            let current_period = Self::get_current_period(dao_id);
+            let mut new_stream_locked_pool = Self::stream_locked_pool(pool_key);
+            let mut new_free_pool = Self::free_pool(pool_key);
+            let mut settled = Vec::new();
+            for (stage, stream) in streams {
+                let vested = Self::vested(&stream, current_period)?;
+                let payout = vested.checked_sub(&stream.claimed).ok_or(DaoError::Overflow)?;
+                let unvested = stream.total.checked_sub(&vested).ok_or(DaoError::Overflow)?;
+                new_stream_locked_pool = new_stream_locked_pool.checked_sub(&payout).and_then(|n| n.checked_sub(&unvested)).ok_or(DaoError::Overflow)?;
+                new_free_pool = new_free_pool.checked_add(&unvested).ok_or(DaoError::Overflow)?;
+                settled.push((stage, stream.recipient, payout, unvested));
+            }
+
+            project_proposal.aborted = true;
+            <ProjectProposals<T>>::insert((dao_id, project_proposal_index), project_proposal.clone());
+            <MortgagePool<T>>::insert(dao_id, new_mortgage_pool);
+            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&project_proposal.proposer, project_proposal.mortgage);
+            <StreamLockedPool<T>>::insert(pool_key, new_stream_locked_pool);
+            <FreePool<T>>::insert(pool_key, new_free_pool);
+            for (stage, recipient, payout, unvested) in settled {
+                if payout > <T::Balance as As<u64>>::sa(0) {
+                    Self::deposit_asset(project_proposal.grant_asset, &recipient, payout);
+                    Self::deposit_event(RawEvent::StreamClaimed(dao_id, project_proposal_index, stage, recipient, payout));
+                }
+                <GrantStreams<T>>::remove((dao_id, project_proposal_index, stage));
+                Self::deposit_event(RawEvent::StreamCancelled(dao_id, project_proposal_index, stage, unvested));
+            }
+            // a failed stage was already rated when it was processed
+            if sender == project_proposal.applicant {
+                Self::adjust_reputation(dao_id, &project_proposal.applicant, reputation::ABORTED);
+            }
+
+            Self::deposit_event(RawEvent::ProjectCancelled(dao_id, project_proposal_index, sender));
+            Ok(())
+        }
 
-                    // update project proposal
-                    <ProjectProposals<T>>::insert(first_unprocessed_project_proposal_index, first_unprocessed_project_proposal.clone());
-                    <UnprocessedQueueHead<T>>::mutate(|n| *n += 1);
-                    <UnprocessedQueueLength<T>>::mutate(|n| *n -= 1);
-
-                    Self::deposit_event(RawEvent::ProcessProjectProposal(
-                        first_unprocessed_project_proposal_index,
-                        first_unprocessed_project_proposal.proposer, 
-                        first_unprocessed_project_proposal.applicant, 
-                        first_unprocessed_project_proposal.status, 
-                        first_unprocessed_project_proposal.round,
-                        first_unprocessed_project_proposal.stage_did_pass,
-                        grant_this_stage));
+        fn on_initialize() {
+            Self::migrate_storage();
+            // only daos with queued proposals are visited, at most MAX_DAOS_PER_BLOCK of them
+            let visits = Self::scheduled_daos_length().min(MAX_DAOS_PER_BLOCK);
+            for _ in 0..visits {
+                let dao_id = Self::unschedule_next_dao();
+                // a proposal waits until the voters of the one before it are credited
+                if Self::pending_credit((dao_id, false)).is_none() {
+                    Self::process_access_proposal(dao_id);
+                }
+                if Self::pending_credit((dao_id, true)).is_none() {
+                    Self::process_project_proposal(dao_id);
+                }
+                Self::credit_voters(dao_id);
+                Self::decay_inactive_energy(dao_id);
+                if Self::has_queued_proposals(dao_id) || Self::decay_cursor(dao_id).is_some() {
+                    Self::schedule_dao(dao_id);
+                }
+            }
+        }
+
+        fn on_finalize() {
+            // extrinsic indices start again in the next block
+            <ExtrinsicFees<T>>::kill();
+
+            #[cfg(feature = "check-invariants")]
+            {
+                // entries not migrated yet do not decode with the current layout
+                if Self::storage_version() >= CURRENT_STORAGE_VERSION {
+                    if let Err((dao_id, invariant)) = Self::check_invariants() {
+                        runtime_io::print("dao invariant broken");
+                        Self::deposit_event(RawEvent::InvariantBroken(dao_id, invariant));
+                    }
                 }
             }
         }
@@ -591,19 +1798,3077 @@ decl_module! {
 }
 
 impl<T: Trait> Module<T> {
-    pub fn is_member(account: &T::AccountId) -> bool {
-        <Members<T>>::exists(account) && Self::members(account).energy > 0
+    pub fn dao_exists(dao_id: DaoId) -> bool {
+        dao_id < Self::daos_count()
+    }
+
+    pub fn asset_exists(asset_id: AssetId) -> bool {
+        asset_id == NATIVE_ASSET_ID || <assets::Module<T>>::is_issued(asset_id)
+    }
+
+    pub fn is_member(dao_id: DaoId, account: &T::AccountId) -> bool {
+        <Members<T>>::exists((dao_id, account.clone())) && Self::members((dao_id, account.clone())).energy > 0
+    }
+
+    /// Access proposals are processed in order, so this holds even after the proposal is reaped.
+    pub fn is_access_processed(dao_id: DaoId, index: u32) -> bool {
+        index < Self::processed_access_proposals_count(dao_id)
+    }
+
+    pub fn get_current_period(dao_id: DaoId) -> u64 {
+        let period_duration = Self::period_duration(dao_id);
+        // an unknown dao has no period duration
+        if period_duration == <T::Moment as As<u64>>::sa(0) {
+            return 0;
+        }
+        <T::Moment as As<u64>>::as_(<timestamp::Module<T>>::get() / period_duration)
+    }
+
+    pub fn has_voting_period_expired(dao_id: DaoId, starting_period: u64) -> bool {
+        Self::get_current_period(dao_id) >= starting_period.saturating_add(Self::voting_period_length(dao_id))
+    }
+
+    pub fn in_vote_period(dao_id: DaoId, starting_period: u64) -> bool {
+        Self::get_current_period(dao_id) >= starting_period && !Self::has_voting_period_expired(dao_id, starting_period)
+    }
+
+    /// Check a vote against the current state, for the transaction pool.
+    /// Returns the number of periods left to vote on the proposal.
+    pub fn validate_vote(dao_id: DaoId, who: &T::AccountId, is_project: bool, index: u32) -> result::Result<u64, DaoError> {
+        if Self::storage_version() < KEYED_LAYOUT_VERSION {
+            return Err(DaoError::MigrationInProgress);
+        }
+        let (starting_period, aborted, voted) = if is_project {
+            if !<ProjectProposals<T>>::exists((dao_id, index)) {
+                return Err(DaoError::InvalidProjectProposalIndex);
+            }
+            let project_proposal = Self::project_proposals((dao_id, index));
+            let voted = <VotesForProject<T>>::exists((dao_id, index, who.clone(), project_proposal.status, project_proposal.round));
+            (project_proposal.starting_period, project_proposal.aborted, voted)
+        } else {
+            if !<AccessProposals<T>>::exists((dao_id, index)) {
+                return Err(DaoError::InvalidAccessProposalIndex);
+            }
+            let access_proposal = Self::access_proposals((dao_id, index));
+            (access_proposal.starting_period, access_proposal.aborted, <VotesForAccess<T>>::exists((dao_id, index, who.clone())))
+        };
+
+        if aborted {
+            return Err(if is_project { DaoError::ProjectProposalAborted } else { DaoError::AccessProposalAborted });
+        }
+        if !Self::is_member(dao_id, who) {
+            return Err(DaoError::NotMember);
+        }
+        if voted {
+            return Err(DaoError::AlreadyVoted);
+        }
+        if Self::get_current_period(dao_id) < starting_period {
+            return Err(DaoError::VotingNotStarted);
+        }
+        if !Self::in_vote_period(dao_id, starting_period) {
+            return Err(DaoError::NotInVotingPeriod);
+        }
+        Ok(starting_period.saturating_add(Self::voting_period_length(dao_id)) - Self::get_current_period(dao_id))
+    }
+
+    /// Validity of a signed vote of `who` in the transaction pool, on top of the validity the
+    /// executive found for the transaction. Votes that would fail in the block are dropped, and
+    /// votes closing soon go first.
+    pub fn validate_vote_transaction(validity: TransactionValidity, who: &T::AccountId, dao_id: DaoId, is_project: bool, index: u32) -> TransactionValidity {
+        match validity {
+            TransactionValidity::Valid { priority, requires, mut provides, longevity } => match Self::validate_vote(dao_id, who, is_project, index) {
+                Ok(periods_left) => {
+                    // one vote per member and proposal in the pool
+                    provides.push((&b"dao_vote"[..], dao_id, is_project, index, who).encode());
+                    TransactionValidity::Valid {
+                        priority: priority.saturating_add(VOTE_PRIORITY / periods_left),
+                        requires,
+                        provides,
+                        longevity,
+                    }
+                },
+                // the pool has no queue for transactions waiting on time, so a vote sent early is
+                // reported unknown with the `Future` code rather than banned as invalid
+                Err(DaoError::VotingNotStarted) => TransactionValidity::Unknown(ApplyError::Future as i8),
+                Err(e) => TransactionValidity::Invalid(e.code()),
+            },
+            validity => validity,
+        }
+    }
+
+    fn create_access_proposal(
+        dao_id: DaoId,
+        sender: T::AccountId,
+        applicant: T::AccountId,
+        kind: ProposalKind,
+        deposit_asset: AssetId,
+        deposit: T::Balance,
+        payment_asset: AssetId,
+        payment_requested: T::Balance,
+        energies_requested: u64,
+        loot_requested: u64,
+        detail: ContentId,
+        tribute_reserved: bool
+    ) -> Result {
+        ensure!(Self::dao_exists(dao_id), DaoError::DaoNotFound.into());
+        ensure!(Self::is_member(dao_id, &sender), DaoError::NotMember.into());
+        ensure!(applicant != T::AccountId::default(), DaoError::ApplicantNotSet.into());
+        ensure!(Self::token_whitelist((dao_id, deposit_asset)), DaoError::TributeAssetNotWhitelisted.into());
+        ensure!(Self::token_whitelist((dao_id, payment_asset)), DaoError::PaymentAssetNotWhitelisted.into());
+        // a sponsored draft has its tribute reserved by the applicant, otherwise the allowance covers it
+        ensure!(tribute_reserved || Self::allowance((dao_id, applicant.clone(), sender.clone(), deposit_asset)) >= deposit, DaoError::ApplicantAllowanceTooLow.into());
+
+        let total_energies_requested = Self::total_energies_requested(dao_id);
+        let new_total_energies_requested = total_energies_requested.checked_add(energies_requested).ok_or(DaoError::Overflow)?;
+
+        let mut new_deposit_pool = Self::deposit_pool((dao_id, deposit_asset));
+        if deposit > <T::Balance as As<u64>>::sa(0) {
+            new_deposit_pool = new_deposit_pool.checked_add(&deposit).ok_or(DaoError::Overflow)?;
+        }
+
+        let access_proposals_count = Self::access_proposals_count(dao_id);
+        let new_access_proposals_count = access_proposals_count.checked_add(1).ok_or(DaoError::Overflow)?;
+        let mut this_starting_period: u64 = Self::get_current_period(dao_id);
+        if access_proposals_count != 0 && Self::access_proposals((dao_id, access_proposals_count - 1)).starting_period > this_starting_period {
+            this_starting_period = Self::access_proposals((dao_id, access_proposals_count - 1)).starting_period + 1;
+        }
+
+        let new_mortgage_pool = Self::mortgage_pool(dao_id).checked_add(&Self::proposal_mortgage(dao_id)).ok_or(DaoError::Overflow)?;
+
+        // create proposal
+        let mut access_proposal = AccessProposal {
+            proposer: sender.clone(),
+            applicant: applicant.clone(),
+            energies_requested: energies_requested,
+            loot_requested: loot_requested,
+            mortgage: Self::proposal_mortgage(dao_id),
+            storage_deposit: <T::Balance as As<u64>>::sa(0),
+            deposit_asset: deposit_asset,
+            deposit: deposit,
+            payment_asset: payment_asset,
+            payment_requested: payment_requested,
+            kind: kind,
+            starting_period: this_starting_period,
+            yes_votes: 0,
+            no_votes: 0,
+            processed: false,
+            did_pass: false,
+            aborted: false,
+            detail: detail,
+        };
+        access_proposal.storage_deposit = Self::storage_deposit_of(&access_proposal)?;
+
+        // everything above only reads, and the funds of the proposer are the last thing that can fail
+        Self::take_funds(&sender, access_proposal.storage_deposit, access_proposal.mortgage)?;
+        if deposit > <T::Balance as As<u64>>::sa(0) {
+            Self::take_reserved_asset(deposit_asset, &applicant, deposit);
+            <DepositPool<T>>::insert((dao_id, deposit_asset), new_deposit_pool);
+            if !tribute_reserved {
+                <Allowance<T>>::mutate((dao_id, applicant.clone(), sender.clone(), deposit_asset), |n| *n -= deposit);
+            }
+        }
+        <MortgagePool<T>>::insert(dao_id, new_mortgage_pool);
+        <TotalEnergiesRequested<T>>::insert(dao_id, new_total_energies_requested);
+        <AccessProposals<T>>::insert((dao_id, access_proposals_count), access_proposal);
+        Self::list_insert(dao_id, ProposalList::OpenAccess, (false, access_proposals_count));
+        Self::index_proposal(dao_id, false, access_proposals_count, &sender, &applicant);
+        <AccessProposalsCount<T>>::insert(dao_id, new_access_proposals_count);
+        Self::schedule_dao(dao_id);
+
+        Self::deposit_event(RawEvent::SubmitAccessProposal(dao_id, sender, applicant, energies_requested, deposit_asset, deposit, this_starting_period));
+        if payment_requested > <T::Balance as As<u64>>::sa(0) {
+            Self::deposit_event(RawEvent::PaymentRequested(dao_id, access_proposals_count, payment_asset, payment_requested));
+        }
+        if let ProposalKind::Whitelist(asset_id) = kind {
+            Self::deposit_event(RawEvent::SubmitWhitelistProposal(dao_id, access_proposals_count, asset_id));
+        }
+        Ok(())
+    }
+
+    fn create_project_proposal(
+        dao_id: DaoId,
+        sender: T::AccountId,
+        applicant: T::AccountId,
+        grant_asset: AssetId,
+        milestone_1_requested: T::Balance,
+        milestone_2_requested: T::Balance,
+        milestone_3_requested: T::Balance,
+        detail: ContentId
+    ) -> Result {
+        Self::ensure_storage_migrated()?;
+        ensure!(Self::dao_exists(dao_id), DaoError::DaoNotFound.into());
+        ensure!(Self::is_member(dao_id, &sender), DaoError::NotMember.into());
+        ensure!(applicant != T::AccountId::default(), DaoError::ApplicantNotSet.into());
+        ensure!(Self::token_whitelist((dao_id, grant_asset)), DaoError::GrantAssetNotWhitelisted.into());
+
+        let this_starting_period = Self::next_project_starting_period(dao_id);
+
+        let new_mortgage_pool = Self::mortgage_pool(dao_id).checked_add(&Self::proposal_mortgage(dao_id)).ok_or(DaoError::Overflow)?;
+
+        // create proposal
+        let mut project_proposal = ProjectProposal {
+            proposer: sender.clone(),
+            applicant: applicant.clone(),
+            mortgage: Self::proposal_mortgage(dao_id),
+            storage_deposit: <T::Balance as As<u64>>::sa(0),
+            starting_period: this_starting_period,
+            grant_asset: grant_asset,
+            milestone_1_requested: milestone_1_requested,
+            milestone_2_requested: milestone_2_requested,
+            milestone_3_requested: milestone_3_requested,
+            yes_votes: 0,
+            no_votes: 0,
+            processed: false,
+            stage_did_pass: false,
+            round: 0,
+            aborted: false,
+            completed: false,
+            status: ProjectStatus::Initialization,
+            detail: detail,
+        };
+        project_proposal.storage_deposit = Self::storage_deposit_of(&project_proposal)?;
+
+        let project_proposals_count = Self::project_proposals_count(dao_id);
+        let new_project_proposals_count = project_proposals_count.checked_add(1).ok_or(DaoError::Overflow)?;
+        let unprocessed_queue_length = Self::unprocssed_queue_length(dao_id);
+        let new_unprocessed_queue_length = unprocessed_queue_length.checked_add(1).ok_or(DaoError::Overflow)?;
+
+        Self::take_funds(&sender, project_proposal.storage_deposit, project_proposal.mortgage)?;
+        <MortgagePool<T>>::insert(dao_id, new_mortgage_pool);
+        <ProjectProposals<T>>::insert((dao_id, project_proposals_count), project_proposal);
+        <ProjectsProcessQueue<T>>::insert((dao_id, Self::unprocssed_queue_head(dao_id) + unprocessed_queue_length), project_proposals_count);
+        Self::list_insert(dao_id, ProposalList::OpenProject(ProjectStatus::Initialization), (true, project_proposals_count));
+        Self::index_proposal(dao_id, true, project_proposals_count, &sender, &applicant);
+        <UnprocessedQueueLength<T>>::insert(dao_id, new_unprocessed_queue_length);
+        Self::schedule_dao(dao_id);
+        <ProjectProposalsCount<T>>::insert(dao_id, new_project_proposals_count);
+
+        Self::deposit_event(RawEvent::SubmitProjectProposal(dao_id, sender, applicant, grant_asset, milestone_1_requested, milestone_2_requested, milestone_3_requested, this_starting_period));
+        Ok(())
+    }
+
+    /// Parse a binary CIDv0 or CIDv1, bounded by `MaxDetailLength`.
+    pub fn parse_content_id(raw: &[u8]) -> result::Result<ContentId, &'static str> {
+        ensure!(raw.len() <= Self::max_detail_length() as usize, DaoError::DetailTooLong.into());
+
+        // CIDv0 is a bare sha2-256 multihash of a dag-pb node
+        if raw.len() == 34 && raw[0] == 0x12 && raw[1] == 0x20 {
+            return Ok(ContentId {
+                version: 0,
+                codec: 0x70,
+                hash_code: 0x12,
+                digest: raw[2..].to_vec(),
+            });
+        }
+
+        ensure!(raw.first() == Some(&1), DaoError::InvalidDetail.into());
+        let mut pos = 1;
+        let codec = Self::read_varint(raw, &mut pos).ok_or(DaoError::InvalidDetail)?;
+        let hash_code = Self::read_varint(raw, &mut pos).ok_or(DaoError::InvalidDetail)?;
+        let digest_length = Self::read_varint(raw, &mut pos).ok_or(DaoError::InvalidDetail)?;
+        ensure!(digest_length > 0 && (raw.len() - pos) as u64 == digest_length, DaoError::InvalidDetail.into());
+
+        Ok(ContentId {
+            version: 1,
+            codec: codec,
+            hash_code: hash_code,
+            digest: raw[pos..].to_vec(),
+        })
+    }
+
+    fn read_varint(raw: &[u8], pos: &mut usize) -> Option<u64> {
+        let mut value: u64 = 0;
+        // a multiformats varint is at most 9 bytes
+        for shift in 0..9 {
+            let byte = *raw.get(*pos)?;
+            *pos += 1;
+            value |= ((byte & 0x7f) as u64) << (shift * 7);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    pub fn proposal_detail(dao_id: DaoId, is_project: bool, index: u32) -> Option<ContentId> {
+        if is_project {
+            if <ProjectProposals<T>>::exists((dao_id, index)) {
+                return Some(Self::project_proposals((dao_id, index)).detail);
+            }
+        } else if <AccessProposals<T>>::exists((dao_id, index)) {
+            return Some(Self::access_proposals((dao_id, index)).detail);
+        }
+        None
+    }
+
+    /// The profiles of the proposer and the applicant of a proposal, if it exists.
+    pub fn proposal_profiles(dao_id: DaoId, is_project: bool, index: u32) -> Option<(Option<Profile>, Option<Profile>)> {
+        let (proposer, applicant) = if is_project {
+            if !<ProjectProposals<T>>::exists((dao_id, index)) {
+                return None;
+            }
+            let project_proposal = Self::project_proposals((dao_id, index));
+            (project_proposal.proposer, project_proposal.applicant)
+        } else {
+            if !<AccessProposals<T>>::exists((dao_id, index)) {
+                return None;
+            }
+            let access_proposal = Self::access_proposals((dao_id, index));
+            (access_proposal.proposer, access_proposal.applicant)
+        };
+        Some((Self::profiles((dao_id, proposer)), Self::profiles((dao_id, applicant))))
+    }
+
+    /// Check that the pools and share totals of every dao agree with its members and open
+    /// proposals, reporting the first invariant that does not hold. The result only means
+    /// something once `StorageVersion` is `CURRENT_STORAGE_VERSION`.
+    pub fn check_invariants() -> result::Result<(), (DaoId, Invariant)> {
+        for dao_id in 0..Self::daos_count() {
+            Self::check_dao_invariants(dao_id).map_err(|invariant| (dao_id, invariant))?;
+        }
+        Ok(())
+    }
+
+    // the list of active members holds exactly the members with energy, once each
+    fn active_members_consistent(dao_id: DaoId) -> bool {
+        let members_count = Self::members_count(dao_id);
+        let with_energy = (0..members_count)
+            .filter(|i| Self::members((dao_id, Self::members_array((dao_id, *i)))).energy > 0)
+            .count() as u32;
+
+        let mut linked = 0u32;
+        let mut prev = None;
+        let mut next = Self::active_members_head(dao_id);
+        while let Some(account) = next {
+            let key = (dao_id, account.clone());
+            let link = Self::member_links(key.clone());
+            if !link.linked || link.prev != prev || Self::members(key).energy == 0 || linked >= members_count {
+                return false;
+            }
+            linked += 1;
+            prev = Some(account);
+            next = link.next;
+        }
+        prev == Self::active_members_tail(dao_id) && linked == with_energy && linked == Self::active_members_count(dao_id)
+    }
+
+    fn check_dao_invariants(dao_id: DaoId) -> result::Result<(), Invariant> {
+        let zero = <T::Balance as As<u64>>::sa(0);
+
+        let mut energies: u128 = 0;
+        let mut loot: u128 = 0;
+        for i in 0..Self::members_count(dao_id) {
+            let member_key = (dao_id, Self::members_array((dao_id, i)));
+            ensure!(<Members<T>>::exists(member_key.clone()), Invariant::MembersArray);
+            let member = Self::members(member_key);
+            energies += member.energy as u128;
+            loot += member.loot as u128;
+        }
+        ensure!(energies == Self::total_energies(dao_id) as u128, Invariant::TotalEnergies);
+        ensure!(Self::active_members_consistent(dao_id), Invariant::ActiveMembers);
+        ensure!(loot == Self::total_loot(dao_id) as u128, Invariant::TotalLoot);
+
+        let open_access_proposals: Vec<_> = (Self::processed_access_proposals_count(dao_id)..Self::access_proposals_count(dao_id))
+            .map(|i| Self::access_proposals((dao_id, i)))
+            .collect();
+        let project_proposals: Vec<_> = (0..Self::project_proposals_count(dao_id))
+            .filter(|i| <ProjectProposals<T>>::exists((dao_id, *i)))
+            .map(|i| Self::project_proposals((dao_id, i)))
+            .collect();
+
+        let energies_requested: u128 = open_access_proposals.iter().map(|p| p.energies_requested as u128).sum();
+        ensure!(energies_requested == Self::total_energies_requested(dao_id) as u128, Invariant::TotalEnergiesRequested);
+
+        let mut mortgages = zero;
+        for mortgage in open_access_proposals.iter().map(|p| p.mortgage)
+            .chain(project_proposals.iter().filter(|p| !p.completed && !p.aborted).map(|p| p.mortgage)) {
+            mortgages = mortgages.checked_add(&mortgage).ok_or(Invariant::MortgagePool)?;
+        }
+        ensure!(mortgages == Self::mortgage_pool(dao_id), Invariant::MortgagePool);
+
+        let streams: Vec<_> = (0..Self::project_proposals_count(dao_id))
+            .flat_map(|index| Self::open_project_streams(dao_id, index))
+            .map(|(_, stream)| stream)
+            .collect();
+        let treasury_assets = Self::treasury_assets(dao_id);
+        for asset_id in 0..=<assets::Module<T>>::assets_count() {
+            let mut streamed = zero;
+            for stream in streams.iter() {
+                if stream.asset == asset_id {
+                    let unclaimed = stream.total.checked_sub(&stream.claimed).ok_or(Invariant::StreamLockedPool(asset_id))?;
+                    streamed = streamed.checked_add(&unclaimed).ok_or(Invariant::StreamLockedPool(asset_id))?;
+                }
+            }
+            ensure!(streamed == Self::stream_locked_pool((dao_id, asset_id)), Invariant::StreamLockedPool(asset_id));
+
+            let mut deposits = zero;
+            for p in open_access_proposals.iter().filter(|p| p.deposit_asset == asset_id) {
+                deposits = deposits.checked_add(&p.deposit).ok_or(Invariant::DepositPool(asset_id))?;
+            }
+            ensure!(deposits == Self::deposit_pool((dao_id, asset_id)), Invariant::DepositPool(asset_id));
+
+            let mut grants = zero;
+            for p in project_proposals.iter().filter(|p| !p.processed && p.grant_asset == asset_id) {
+                grants = grants.checked_add(&Self::stage_grant(p)).ok_or(Invariant::GrantLockedPool(asset_id))?;
+            }
+            ensure!(grants == Self::grant_locked_pool((dao_id, asset_id)), Invariant::GrantLockedPool(asset_id));
+
+            // rage quit only redeems the treasury assets
+            ensure!(Self::free_pool((dao_id, asset_id)) == zero || treasury_assets.contains(&asset_id), Invariant::FreePool(asset_id));
+        }
+
+        let mut rewards = zero;
+        for period in Self::open_reward_periods(dao_id) {
+            let (remaining, _) = Self::period_vote_rewards((dao_id, period)).ok_or(Invariant::VoteRewardPool)?;
+            rewards = rewards.checked_add(&remaining).ok_or(Invariant::VoteRewardPool)?;
+        }
+        ensure!(rewards == Self::vote_reward_pool(dao_id), Invariant::VoteRewardPool);
+        Ok(())
+    }
+
+    /// The part of a stream vested by `period`.
+    fn vested(stream: &GrantStream<T::AccountId, T::Balance>, period: u64) -> result::Result<T::Balance, DaoError> {
+        let elapsed = period.saturating_sub(stream.start_period);
+        if elapsed >= stream.periods {
+            return Ok(stream.total);
+        }
+        stream.total.checked_mul(&<T::Balance as As<u64>>::sa(elapsed)).ok_or(DaoError::Overflow)?
+            .checked_div(&<T::Balance as As<u64>>::sa(stream.periods)).ok_or(DaoError::Overflow)
+    }
+
+    /// The grant streams of a project, with the amount claimable now.
+    pub fn project_streams(dao_id: DaoId, index: u32) -> Vec<(ProjectStatus, GrantStream<T::AccountId, T::Balance>, T::Balance)> {
+        if !Self::dao_exists(dao_id) {
+            return Vec::new();
+        }
+        let current_period = Self::get_current_period(dao_id);
+        Self::open_project_streams(dao_id, index).into_iter()
+            .map(|(stage, stream)| {
+                let claimable = Self::vested(&stream, current_period).ok()
+                    .and_then(|vested| vested.checked_sub(&stream.claimed))
+                    .unwrap_or_else(|| <T::Balance as As<u64>>::sa(0));
+                (stage, stream, claimable)
+            })
+            .collect()
+    }
+
+    fn access_voters(dao_id: DaoId, index: u32) -> Vec<T::AccountId> {
+        (0..Self::access_voters_count((dao_id, index))).map(|position| Self::access_voter((dao_id, index, position))).collect()
+    }
+
+    fn project_voters(dao_id: DaoId, index: u32) -> Vec<(T::AccountId, ProjectStatus, u64)> {
+        (0..Self::project_voters_count((dao_id, index))).map(|position| Self::project_voter((dao_id, index, position))).collect()
+    }
+
+    // a project has at most one stream per paid stage
+    fn open_project_streams(dao_id: DaoId, index: u32) -> Vec<(ProjectStatus, GrantStream<T::AccountId, T::Balance>)> {
+        [ProjectStatus::Milestone1, ProjectStatus::Milestone2, ProjectStatus::Milestone3].iter()
+            .filter_map(|stage| Self::grant_streams((dao_id, index, *stage)).map(|stream| (*stage, stream)))
+            .collect()
+    }
+
+    fn stage_grant(project_proposal: &ProjectProposal<T::AccountId, T::Balance>) -> T::Balance {
+        match project_proposal.status {
+            ProjectStatus::Initialization => <T::Balance as As<u64>>::sa(0),
+            ProjectStatus::Milestone1 => project_proposal.milestone_1_requested,
+            ProjectStatus::Milestone2 => project_proposal.milestone_2_requested,
+            ProjectStatus::Milestone3 => project_proposal.milestone_3_requested,
+        }
+    }
+
+    fn storage_deposit_of<P: Encode>(proposal: &P) -> result::Result<T::Balance, &'static str> {
+        Self::byte_deposit().checked_mul(&<T::Balance as As<u64>>::sa(proposal.encode().len() as u64)).ok_or(DaoError::Overflow.into())
+    }
+
+    /// Reserve a storage deposit and take a mortgage or tribute, both or neither.
+    fn take_funds(who: &T::AccountId, storage_deposit: T::Balance, value: T::Balance) -> Result {
+        <balances::Module<T> as ReservableCurrency<_>>::reserve(who, storage_deposit)?;
+        if let Err(e) = <balances::Module<T> as Currency<_>>::withdraw(who, value, WithdrawReason::Reserve, ExistenceRequirement::KeepAlive) {
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(who, storage_deposit);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn withdraw_asset(asset_id: AssetId, who: &T::AccountId, value: T::Balance) -> Result {
+        if asset_id == NATIVE_ASSET_ID {
+            let _ = <balances::Module<T> as Currency<_>>::withdraw(who, value, WithdrawReason::Reserve, ExistenceRequirement::KeepAlive)?;
+            Ok(())
+        } else {
+            <assets::Module<T>>::withdraw(asset_id, who, value)
+        }
+    }
+
+    fn deposit_asset(asset_id: AssetId, who: &T::AccountId, value: T::Balance) {
+        if asset_id == NATIVE_ASSET_ID {
+            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(who, value);
+        } else {
+            let _ = <assets::Module<T>>::deposit(asset_id, who, value);
+        }
+    }
+
+    fn reserve_asset(asset_id: AssetId, who: &T::AccountId, value: T::Balance) -> Result {
+        if asset_id == NATIVE_ASSET_ID {
+            <balances::Module<T> as ReservableCurrency<_>>::reserve(who, value)
+        } else {
+            <assets::Module<T>>::reserve(asset_id, who, value)
+        }
+    }
+
+    fn unreserve_asset(asset_id: AssetId, who: &T::AccountId, value: T::Balance) {
+        if asset_id == NATIVE_ASSET_ID {
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::unreserve(who, value);
+        } else {
+            <assets::Module<T>>::unreserve(asset_id, who, value);
+        }
+    }
+
+    // the allowance guarantees that `value` is reserved from `who`
+    fn take_reserved_asset(asset_id: AssetId, who: &T::AccountId, value: T::Balance) {
+        if asset_id == NATIVE_ASSET_ID {
+            let _ = <balances::Module<T> as ReservableCurrency<_>>::slash_reserved(who, value);
+        } else {
+            <assets::Module<T>>::slash_reserved(asset_id, who, value);
+        }
+    }
+
+    /// Charge `weight * WeightFee` from `who`, handed to the transaction payment handler of `balances`.
+    fn charge_weight(who: &T::AccountId, weight: u64) -> Result {
+        let fee = Self::weight_fee().checked_mul(&<T::Balance as As<u64>>::sa(weight)).ok_or(DaoError::Overflow)?;
+        if fee > <T::Balance as As<u64>>::sa(0) {
+            let imbalance = <balances::Module<T> as Currency<_>>::withdraw(who, fee, WithdrawReason::TransactionPayment, ExistenceRequirement::KeepAlive)?;
+            <T as balances::Trait>::TransactionPayment::on_unbalanced(imbalance);
+        }
+        Ok(())
     }
 
-    pub fn get_current_period() -> u64 {
-        <T::Moment as As<u64>>::as_(<timestamp::Module<T>>::get() / Self::period_duration())
+    // deposit_into_existing issues the refund anew, but the fees it comes out of were burned
+    // by `on_unbalanced` in this very extrinsic, so a vote never adds to the total issuance
+    fn refund_vote_fee(who: &T::AccountId) {
+        let (index, paid) = Self::extrinsic_fees();
+        if index != <system::Module<T>>::extrinsic_index() {
+            return;
+        }
+        let refund = if Self::vote_fee_refund() < paid { Self::vote_fee_refund() } else { paid };
+        if refund > <T::Balance as As<u64>>::sa(0) {
+            <ExtrinsicFees<T>>::put((index, paid - refund));
+            let _ = <balances::Module<T> as Currency<_>>::deposit_into_existing(who, refund);
+        }
+    }
+
+    fn index_proposal(dao_id: DaoId, is_project: bool, index: u32, proposer: &T::AccountId, applicant: &T::AccountId) {
+        Self::list_insert(dao_id, ProposalList::ByProposer(proposer.clone()), (is_project, index));
+        Self::list_insert(dao_id, ProposalList::ByApplicant(applicant.clone()), (is_project, index));
+    }
+
+    fn unindex_proposal(dao_id: DaoId, is_project: bool, index: u32, proposer: &T::AccountId, applicant: &T::AccountId) {
+        Self::list_remove(dao_id, ProposalList::ByProposer(proposer.clone()), (is_project, index));
+        Self::list_remove(dao_id, ProposalList::ByApplicant(applicant.clone()), (is_project, index));
+    }
+
+    /// Append `proposal` to a list, unless it is in it already. Returns whether it was added.
+    fn list_insert(dao_id: DaoId, list: ProposalList<T::AccountId>, proposal: (bool, u32)) -> bool {
+        let position_key = (dao_id, list.clone(), proposal);
+        if <ProposalListPosition<T>>::exists(position_key.clone()) {
+            return false;
+        }
+        let length = Self::proposal_list_length((dao_id, list.clone()));
+        <ProposalLists<T>>::insert((dao_id, list.clone(), length), proposal);
+        <ProposalListPosition<T>>::insert(position_key, length);
+        // a list holds distinct entries of u32 indices, this only saturates in theory
+        <ProposalListLength<T>>::insert((dao_id, list), length.saturating_add(1));
+        true
+    }
+
+    fn list_remove(dao_id: DaoId, list: ProposalList<T::AccountId>, proposal: (bool, u32)) {
+        let position = match <ProposalListPosition<T>>::take((dao_id, list.clone(), proposal)) {
+            Some(position) => position,
+            None => return,
+        };
+        let last = Self::proposal_list_length((dao_id, list.clone())).saturating_sub(1);
+        let moved = <ProposalLists<T>>::take((dao_id, list.clone(), last));
+        if position != last {
+            <ProposalLists<T>>::insert((dao_id, list.clone(), position), moved);
+            <ProposalListPosition<T>>::insert((dao_id, list.clone(), moved), position);
+        }
+        <ProposalListLength<T>>::insert((dao_id, list), last);
+    }
+
+    /// Up to `limit` entries of a list from position `start`, at most `MAX_PROPOSALS_PAGE`.
+    /// A removal moves the last entry of the list, so pages read across removals may miss it.
+    pub fn proposal_list(dao_id: DaoId, list: ProposalList<T::AccountId>, start: u32, limit: u32) -> Vec<(bool, u32)> {
+        let limit = if limit < MAX_PROPOSALS_PAGE { limit } else { MAX_PROPOSALS_PAGE };
+        let length = Self::proposal_list_length((dao_id, list.clone()));
+        let end = if start.saturating_add(limit) < length { start + limit } else { length };
+        (start..end).map(|position| Self::proposal_list_entry((dao_id, list.clone(), position))).collect()
+    }
+
+    /// Link `who` at the tail of the active members once it has energy, and unlink it
+    /// once it has none left.
+    fn sync_active_member(dao_id: DaoId, who: &T::AccountId, energy: u64) {
+        let key = (dao_id, who.clone());
+        let mut link = Self::member_links(key.clone());
+        if energy > 0 && !link.linked {
+            let tail = Self::active_members_tail(dao_id);
+            match tail {
+                Some(ref tail) => <MemberLinks<T>>::mutate((dao_id, tail.clone()), |l| l.next = Some(who.clone())),
+                None => <ActiveMembersHead<T>>::insert(dao_id, who.clone()),
+            }
+            <MemberLinks<T>>::insert(key, MemberLink { prev: tail, next: None, linked: true });
+            <ActiveMembersTail<T>>::insert(dao_id, who.clone());
+            <ActiveMembersCount<T>>::mutate(dao_id, |n| *n = n.saturating_add(1));
+        } else if energy == 0 && link.linked {
+            match link.prev {
+                Some(ref prev) => <MemberLinks<T>>::mutate((dao_id, prev.clone()), |l| l.next = link.next.clone()),
+                None => <ActiveMembersHead<T>>::mutate(dao_id, |head| *head = link.next.clone()),
+            }
+            match link.next {
+                Some(ref next) => <MemberLinks<T>>::mutate((dao_id, next.clone()), |l| l.prev = link.prev.clone()),
+                None => <ActiveMembersTail<T>>::mutate(dao_id, |tail| *tail = link.prev.clone()),
+            }
+            link.prev = None;
+            link.linked = false;
+            <MemberLinks<T>>::insert(key, link);
+            <ActiveMembersCount<T>>::mutate(dao_id, |n| *n = n.saturating_sub(1));
+        }
+    }
+
+    /// Up to `limit` active members following `after`, or from the first one. Paging from the
+    /// last member of the previous page is not disturbed by members leaving in between, but a
+    /// member that left and came back is linked again at the tail, so paging from it ends early.
+    /// A client that collected fewer than `active_members_count` members starts over.
+    pub fn active_members(dao_id: DaoId, after: Option<T::AccountId>, limit: u32) -> Vec<MemberInfo<T::AccountId>> {
+        let limit = if limit < MAX_MEMBERS_PAGE { limit } else { MAX_MEMBERS_PAGE };
+        let mut next = match after {
+            Some(account) => Self::member_links((dao_id, account)).next,
+            None => Self::active_members_head(dao_id),
+        };
+
+        let mut page = Vec::new();
+        while let Some(account) = next {
+            if page.len() as u32 >= limit {
+                break;
+            }
+            let key = (dao_id, account.clone());
+            let link = Self::member_links(key.clone());
+            // a member that left after `after` was read keeps pointing forward
+            if link.linked {
+                let member = Self::members(key.clone());
+                page.push(MemberInfo {
+                    account: account,
+                    energy: member.energy,
+                    loot: member.loot,
+                    joined_period: Self::joined_period(key.clone()),
+                    proposals_voted: Self::proposals_voted(key),
+                });
+            }
+            next = link.next;
+        }
+        page
+    }
+
+    fn note_vote(dao_id: DaoId, who: &T::AccountId, is_project: bool, index: u32) {
+        if Self::list_insert(dao_id, ProposalList::VotedBy(who.clone()), (is_project, index)) {
+            <ProposalsVoted<T>>::mutate((dao_id, who.clone()), |n| *n = n.saturating_add(1));
+        }
     }
 
-    pub fn has_voting_period_expired(starting_period: u64) -> bool {
-        Self::get_current_period() >= (starting_period + Self::voting_period_length())
+    fn note_treasury_asset(dao_id: DaoId, asset_id: AssetId) {
+        let mut treasury_assets = Self::treasury_assets(dao_id);
+        if !treasury_assets.contains(&asset_id) {
+            treasury_assets.push(asset_id);
+            <TreasuryAssets<T>>::insert(dao_id, treasury_assets);
+        }
     }
 
-    pub fn in_vote_period(starting_period: u64) -> bool {
-        Self::get_current_period() >= starting_period && !Self::has_voting_period_expired(starting_period)
+    fn lock_vote(dao_id: DaoId, who: &T::AccountId, starting_period: u64) {
+        let voting_ends = starting_period.saturating_add(Self::voting_period_length(dao_id));
+        if voting_ends > Self::vote_locked_until((dao_id, who.clone())) {
+            <VoteLockedUntil<T>>::insert((dao_id, who.clone()), voting_ends);
+        }
     }
-}
\ No newline at end of file
+
+    // the member with the energy lost to inactivity given back, and how much was given back
+    fn with_restored_energy(dao_id: DaoId, who: &T::AccountId) -> result::Result<(Member, u64), DaoError> {
+        let key = (dao_id, who.clone());
+        let mut member = Self::members(key.clone());
+        let restored = Self::decayed_energy(key);
+        member.energy = member.energy.checked_add(restored).ok_or(DaoError::Overflow)?;
+        Ok((member, restored))
+    }
+
+    fn note_activity(dao_id: DaoId, who: &T::AccountId, restored: u64, new_total_energies: u64) {
+        let key = (dao_id, who.clone());
+        <ActiveSince<T>>::insert(key.clone(), Self::proposals_processed(dao_id));
+        <LastVotePeriod<T>>::insert(key.clone(), Self::get_current_period(dao_id));
+        <DecayedPeriods<T>>::remove(key.clone());
+        if restored > 0 {
+            <DecayedEnergy<T>>::remove(key);
+            <TotalEnergies<T>>::insert(dao_id, new_total_energies);
+            Self::deposit_event(RawEvent::EnergyRestored(dao_id, who.clone(), restored));
+        }
+    }
+
+    // the voters are credited by `credit_voters`, a batch per block
+    fn note_processed(dao_id: DaoId, is_project: bool, index: u32, start: u32, end: u32, rewarded: bool) {
+        let processed = Self::proposals_processed(dao_id).saturating_add(1);
+        <ProposalsProcessed<T>>::insert(dao_id, processed);
+        if is_project {
+            <ProjectVotersCredited<T>>::insert((dao_id, index), end);
+        }
+        if start < end {
+            <PendingCredits<T>>::insert((dao_id, is_project), VoterCredit { index: index, next: start, end: end, processed: processed, rewarded: rewarded });
+        }
+    }
+
+    /// Note up to `MAX_CREDITED_VOTERS` voters of the processed proposals of a dao as active,
+    /// and give those of a proposal settled as voted a vote reward point.
+    fn credit_voters(dao_id: DaoId) {
+        let mut budget = MAX_CREDITED_VOTERS;
+        for is_project in [false, true].iter() {
+            let key = (dao_id, *is_project);
+            let mut credit = match Self::pending_credit(key) {
+                Some(credit) => credit,
+                None => continue,
+            };
+            let mut voters = Vec::new();
+            while credit.next < credit.end && budget > 0 {
+                let voter = if *is_project {
+                    Self::project_voter((dao_id, credit.index, credit.next)).0
+                } else {
+                    Self::access_voter((dao_id, credit.index, credit.next))
+                };
+                voters.push(voter);
+                credit.next += 1;
+                budget -= 1;
+            }
+
+            if credit.rewarded {
+                Self::credit_vote_rewards(dao_id, &voters);
+            }
+            for voter in voters {
+                <ActiveSince<T>>::insert((dao_id, voter), credit.processed);
+            }
+            if credit.next < credit.end {
+                <PendingCredits<T>>::insert(key, credit);
+            } else {
+                <PendingCredits<T>>::remove(key);
+            }
+        }
+    }
+
+    /// Burn `DecayPercent` of the energy of an inactive member for each period since its last
+    /// vote not decayed yet. A visit reads at most `MAX_DECAY_MEMBERS` members and the next one
+    /// goes on from where it stopped. A member keeps at least one energy, so that it can still
+    /// vote it back.
+    fn decay_inactive_energy(dao_id: DaoId) {
+        let decay_after_missed = Self::decay_after_missed(dao_id);
+        let decay_percent = Self::decay_percent(dao_id);
+        if decay_after_missed == 0 || decay_percent == 0 {
+            return;
+        }
+
+        let current_period = Self::get_current_period(dao_id);
+        let processed = Self::proposals_processed(dao_id);
+        let mut burned: u64 = 0;
+        let mut next = match Self::decay_cursor(dao_id) {
+            Some(account) => Some(account),
+            None => Self::active_members_head(dao_id),
+        };
+        for _ in 0..MAX_DECAY_MEMBERS {
+            let account = match next {
+                Some(account) => account,
+                None => break,
+            };
+            let key = (dao_id, account.clone());
+            let link = Self::member_links(key.clone());
+            // a member that left after the last visit keeps pointing forward
+            next = link.next;
+            if !link.linked || processed.saturating_sub(Self::active_since(key.clone())) < decay_after_missed {
+                continue;
+            }
+
+            let missed = current_period.saturating_sub(Self::last_vote_period(key.clone()));
+            let decayed_periods = Self::decayed_periods(key.clone());
+            if missed <= decayed_periods {
+                continue;
+            }
+            let periods = missed - decayed_periods;
+            let periods = if periods < MAX_DECAY_PERIODS { periods } else { MAX_DECAY_PERIODS };
+            <DecayedPeriods<T>>::insert(key.clone(), decayed_periods + periods);
+
+            let mut member = Self::members(key.clone());
+            let mut energy = member.energy;
+            for _ in 0..periods {
+                let decay = (energy as u128 * decay_percent as u128 / 100) as u64;
+                energy -= if decay < energy { decay } else { energy - 1 };
+            }
+            let decayed = member.energy - energy;
+            if decayed == 0 {
+                continue;
+            }
+
+            member.energy = energy;
+            <Members<T>>::insert(key.clone(), member);
+            <DecayedEnergy<T>>::mutate(key, |n| *n = n.saturating_add(decayed));
+            burned = burned.saturating_add(decayed);
+            Self::deposit_event(RawEvent::EnergyDecayed(dao_id, account, decayed));
+        }
+        match next {
+            Some(account) => <DecayCursor<T>>::insert(dao_id, account),
+            None => <DecayCursor<T>>::remove(dao_id),
+        }
+        if burned > 0 {
+            <TotalEnergies<T>>::mutate(dao_id, |n| *n = n.saturating_sub(burned));
+        }
+    }
+
+    /// Give each of `voters` a point of the vote reward of the current period, claimable once
+    /// the period is over.
+    fn credit_vote_rewards(dao_id: DaoId, voters: &[T::AccountId]) {
+        if Self::vote_reward_budget() == <T::Balance as As<u64>>::sa(0) || voters.is_empty() {
+            return;
+        }
+        let period = Self::get_current_period(dao_id);
+        for voter in voters {
+            <VotePoints<T>>::mutate((dao_id, period, voter.clone()), |n| *n = n.saturating_add(1));
+            <VoteRewardPeriods<T>>::mutate((dao_id, voter.clone()), |periods| push_unique(periods, period));
+        }
+        <PeriodVotePoints<T>>::mutate((dao_id, period), |n| *n = n.saturating_add(voters.len() as u32));
+    }
+
+    fn add_new_member(dao_id: DaoId, who: T::AccountId, energy: u64, loot: u64) {
+        let member = Member {
+            energy: energy,
+            loot: loot,
+            highest_index_yes_vote: 0,
+        };
+
+        <Members<T>>::insert((dao_id, who.clone()), member);
+        <MembersArray<T>>::insert((dao_id, Self::members_count(dao_id)), who.clone());
+        <MembersCount<T>>::mutate(dao_id, |n| *n = n.saturating_add(1));
+        <JoinedPeriod<T>>::insert((dao_id, who.clone()), Self::get_current_period(dao_id));
+        <ActiveSince<T>>::insert((dao_id, who.clone()), Self::proposals_processed(dao_id));
+        <LastVotePeriod<T>>::insert((dao_id, who.clone()), Self::get_current_period(dao_id));
+        <DecayedPeriods<T>>::remove((dao_id, who.clone()));
+        Self::sync_active_member(dao_id, &who, energy);
+
+        Self::deposit_event(RawEvent::NewMember(dao_id, who.clone(), energy));
+        if loot > 0 {
+            Self::deposit_event(RawEvent::LootGranted(dao_id, who, loot));
+        }
+    }
+
+    fn next_project_starting_period(dao_id: DaoId) -> u64 {
+        let mut this_starting_period: u64 = Self::get_current_period(dao_id);
+        let unprocessed_queue_length = Self::unprocssed_queue_length(dao_id);
+        if unprocessed_queue_length != 0 {
+            let last_queued = Self::projects_process_queue((dao_id, Self::unprocssed_queue_head(dao_id) + unprocessed_queue_length - 1));
+            let last_starting_period = Self::project_proposals((dao_id, last_queued)).starting_period;
+            if last_starting_period > this_starting_period {
+                this_starting_period = last_starting_period + 1;
+            }
+        }
+        this_starting_period
+    }
+
+    // dao calls wait until the dao of version 0 is imported as dao 0
+    fn ensure_storage_migrated() -> Result {
+        ensure!(Self::storage_version() >= KEYED_LAYOUT_VERSION, DaoError::MigrationInProgress.into());
+        Ok(())
+    }
+
+    /// Migrate at most `MIGRATION_BATCH` entries towards the next storage version, and bump
+    /// `StorageVersion` once the last dao is done.
+    fn migrate_storage() {
+        let version = Self::storage_version();
+        if version >= CURRENT_STORAGE_VERSION {
+            return;
+        }
+        // a summoned dao of version 0 becomes dao 0
+        if version == 0 && Self::daos_count() == 0 && RuntimeStorage.exists(&Self::key_v0(b"Summoner", &())) {
+            <DaosCount<T>>::put(1);
+        }
+
+        let (mut dao_id, mut index) = Self::migration_cursor();
+        for _ in 0..MIGRATION_BATCH {
+            if dao_id >= Self::daos_count() {
+                <StorageVersion<T>>::put(version + 1);
+                <MigrationCursor<T>>::kill();
+                Self::deposit_event(RawEvent::StorageMigrated(version + 1));
+                return;
+            }
+
+            let dao_done = match version {
+                0 => Self::import_single_dao(dao_id, index),
+                1 => Self::index_stored_proposal(dao_id, index),
+                2 => Self::link_stored_member(dao_id, index),
+                _ => Self::schedule_stored_dao(dao_id),
+            };
+            if dao_done {
+                dao_id += 1;
+                index = 0;
+            } else {
+                index += 1;
+            }
+        }
+        <MigrationCursor<T>>::put((dao_id, index));
+    }
+
+    // version 1: the single dao of version 0 becomes dao 0, its values first, then its members,
+    // its proposals, its project queue, and the votes on its proposals. Old entries are removed
+    // as they are read. Allowances of version 0 reserved nothing and named no spender, and the
+    // votes on past stages of a project cannot be found from the project, so both are dropped.
+    fn import_single_dao(dao_id: DaoId, index: u32) -> bool {
+        if dao_id != 0 {
+            return true;
+        }
+        if index == 0 {
+            Self::import_values_v0();
+            return false;
+        }
+
+        let members = Self::members_count(0) as u64;
+        let access_proposals = Self::access_proposals_count(0) as u64;
+        let project_proposals = Self::project_proposals_count(0) as u64;
+        let queued = Self::unprocssed_queue_length(0) as u64;
+        let mut i = index as u64 - 1;
+        if i < members {
+            Self::import_member_v0(i as u32);
+            return false;
+        }
+        i -= members;
+        if i < access_proposals {
+            let key = i as u32;
+            if let Some(old) = Self::take_v0::<_, AccessProposalV0<T::AccountId, T::Balance>>(b"AccessProposals", &key) {
+                let detail = Self::content_id_v0(&old.detail);
+                <AccessProposals<T>>::insert((0, key), old.upgrade(detail));
+            }
+            return false;
+        }
+        i -= access_proposals;
+        if i < project_proposals {
+            let key = i as u32;
+            if let Some(old) = Self::take_v0::<_, ProjectProposalV0<T::AccountId, T::Balance>>(b"ProjectProposals", &key) {
+                let detail = Self::content_id_v0(&old.detail);
+                <ProjectProposals<T>>::insert((0, key), old.upgrade(detail));
+            }
+            return false;
+        }
+        i -= project_proposals;
+        if i < queued {
+            let position = Self::unprocssed_queue_head(0).wrapping_add(i as u32);
+            if let Some(project_index) = Self::take_v0::<_, u32>(b"ProjectsProcessQueue", &position) {
+                <ProjectsProcessQueue<T>>::insert((0, position), project_index);
+            }
+            return false;
+        }
+        i -= queued;
+        if i < access_proposals * members {
+            Self::import_access_vote_v0((i / members) as u32, Self::members_array((0, (i % members) as u32)));
+            return false;
+        }
+        i -= access_proposals * members;
+        if i < project_proposals * members {
+            Self::import_project_vote_v0((i / members) as u32, Self::members_array((0, (i % members) as u32)));
+            return false;
+        }
+        true
+    }
+
+    fn import_values_v0() {
+        // the funds were withdrawn from their owners, so the pools move as they are
+        let free_pool: T::Balance = Self::take_v0(b"FreePool", &()).unwrap_or_default();
+        let mortgage_pool: T::Balance = Self::take_v0(b"MortgagePool", &()).unwrap_or_default();
+        let deposit_pool: T::Balance = Self::take_v0(b"DepositPool", &()).unwrap_or_default();
+        let grant_locked_pool: T::Balance = Self::take_v0(b"GrantLockedPool", &()).unwrap_or_default();
+        <FreePool<T>>::insert((0, NATIVE_ASSET_ID), free_pool);
+        <MortgagePool<T>>::insert(0, mortgage_pool);
+        <DepositPool<T>>::insert((0, NATIVE_ASSET_ID), deposit_pool);
+        <GrantLockedPool<T>>::insert((0, NATIVE_ASSET_ID), grant_locked_pool);
+        <TokenWhitelist<T>>::insert((0, NATIVE_ASSET_ID), true);
+        Self::note_treasury_asset(0, NATIVE_ASSET_ID);
+
+        <TotalEnergies<T>>::insert(0, Self::take_v0::<_, u64>(b"TotalEnergies", &()).unwrap_or_default());
+        <TotalEnergiesRequested<T>>::insert(0, Self::take_v0::<_, u64>(b"TotalEnergiesRequested", &()).unwrap_or_default());
+        <MembersCount<T>>::insert(0, Self::take_v0::<_, u32>(b"MembersCount", &()).unwrap_or_default());
+        <AccessProposalsCount<T>>::insert(0, Self::take_v0::<_, u32>(b"AccessProposalsCount", &()).unwrap_or_default());
+        <ProcessedAccessProposalsCount<T>>::insert(0, Self::take_v0::<_, u32>(b"ProcessedAccessProposalsCount", &()).unwrap_or_default());
+        <ProjectProposalsCount<T>>::insert(0, Self::take_v0::<_, u32>(b"ProjectProposalsCount", &()).unwrap_or_default());
+        <UnprocessedQueueHead<T>>::insert(0, Self::take_v0::<_, u32>(b"UnprocessedQueueHead", &()).unwrap_or_default());
+        <UnprocessedQueueLength<T>>::insert(0, Self::take_v0::<_, u32>(b"UnprocessedQueueLength", &()).unwrap_or_default());
+
+        if let Some(summoner) = Self::take_v0::<_, T::AccountId>(b"Summoner", &()) {
+            <Summoner<T>>::insert(0, summoner);
+        }
+        <SummoningTime<T>>::insert(0, Self::take_v0::<_, T::Moment>(b"SummoningTime", &()).unwrap_or_default());
+        // energy was not transferable, and neither decay nor grant streams existed
+        <PeriodDuration<T>>::insert(0, Self::take_v0::<_, T::Moment>(b"PeriodDuration", &()).unwrap_or_default());
+        <VotingPeriodLength<T>>::insert(0, Self::take_v0::<_, u64>(b"VotingPeriodLength", &()).unwrap_or_default());
+        <AbortWindow<T>>::insert(0, Self::take_v0::<_, u64>(b"AbortWindow", &()).unwrap_or_default());
+        <ProposalMortgage<T>>::insert(0, Self::take_v0::<_, T::Balance>(b"ProposalMortgage", &()).unwrap_or_default());
+    }
+
+    fn import_member_v0(index: u32) {
+        if let Some(who) = Self::take_v0::<_, T::AccountId>(b"MembersArray", &index) {
+            if let Some(old) = Self::take_v0::<_, MemberV0>(b"Members", &who) {
+                <Members<T>>::insert((0, who.clone()), old.upgrade());
+            }
+            <MembersArray<T>>::insert((0, index), who);
+        }
+    }
+
+    fn import_access_vote_v0(index: u32, who: T::AccountId) {
+        if let Some(vote) = Self::take_v0::<_, bool>(b"VotesForAccess", &(index, who.clone())) {
+            <VotesForAccess<T>>::insert((0, index, who.clone()), vote);
+            let voters_count = Self::access_voters_count((0, index));
+            <AccessVoters<T>>::insert((0, index, voters_count), who.clone());
+            <AccessVotersCount<T>>::insert((0, index), voters_count.saturating_add(1));
+            Self::lock_vote(0, &who, Self::access_proposals((0, index)).starting_period);
+        }
+    }
+
+    // the vote on the current stage and round of the project
+    fn import_project_vote_v0(index: u32, who: T::AccountId) {
+        let project_proposal = Self::project_proposals((0, index));
+        let (status, round) = (project_proposal.status, project_proposal.round);
+        if let Some(vote) = Self::take_v0::<_, bool>(b"VotesForProject", &(index, who.clone(), status, round)) {
+            <VotesForProject<T>>::insert((0, index, who.clone(), status, round), vote);
+            let voters_count = Self::project_voters_count((0, index));
+            <ProjectVoters<T>>::insert((0, index, voters_count), (who.clone(), status, round));
+            <ProjectVotersCount<T>>::insert((0, index), voters_count.saturating_add(1));
+            Self::lock_vote(0, &who, project_proposal.starting_period);
+        }
+    }
+
+    // version 2: the access proposals of a dao, then its project proposals. Entries are only
+    // added when missing, so proposals created while this runs are not indexed twice.
+    fn index_stored_proposal(dao_id: DaoId, index: u32) -> bool {
+        let access_proposals_count = Self::access_proposals_count(dao_id);
+        if index < access_proposals_count {
+            if <AccessProposals<T>>::exists((dao_id, index)) {
+                let access_proposal = Self::access_proposals((dao_id, index));
+                Self::index_proposal(dao_id, false, index, &access_proposal.proposer, &access_proposal.applicant);
+                if !Self::is_access_processed(dao_id, index) {
+                    Self::list_insert(dao_id, ProposalList::OpenAccess, (false, index));
+                }
+                for voter in Self::access_voters(dao_id, index) {
+                    Self::list_insert(dao_id, ProposalList::VotedBy(voter), (false, index));
+                }
+            }
+        } else if index - access_proposals_count < Self::project_proposals_count(dao_id) {
+            let project_index = index - access_proposals_count;
+            if <ProjectProposals<T>>::exists((dao_id, project_index)) {
+                let project_proposal = Self::project_proposals((dao_id, project_index));
+                Self::index_proposal(dao_id, true, project_index, &project_proposal.proposer, &project_proposal.applicant);
+                if !project_proposal.processed {
+                    Self::list_insert(dao_id, ProposalList::OpenProject(project_proposal.status), (true, project_index));
+                }
+                for (voter, _, _) in Self::project_voters(dao_id, project_index) {
+                    Self::list_insert(dao_id, ProposalList::VotedBy(voter), (true, project_index));
+                }
+            }
+        } else {
+            return true;
+        }
+        false
+    }
+
+    // version 3: the members of a dao in the order they joined, which also counts the
+    // proposals voted on from the history built by version 2
+    fn link_stored_member(dao_id: DaoId, index: u32) -> bool {
+        if index >= Self::members_count(dao_id) {
+            return true;
+        }
+        let who = Self::members_array((dao_id, index));
+        let key = (dao_id, who.clone());
+        Self::sync_active_member(dao_id, &who, Self::members(key.clone()).energy);
+        <ProposalsVoted<T>>::insert(key, Self::proposal_list_length((dao_id, ProposalList::VotedBy(who))));
+        false
+    }
+
+    // version 4: the daos with proposals queued before the schedule existed
+    fn schedule_stored_dao(dao_id: DaoId) -> bool {
+        if Self::has_queued_proposals(dao_id) {
+            Self::schedule_dao(dao_id);
+        }
+        true
+    }
+
+    // the key of the value or map entry `name` as the dao of version 0 stored it
+    fn key_v0<K: Encode>(name: &[u8], key: &K) -> Vec<u8> {
+        let mut raw_key = b"Dao ".to_vec();
+        raw_key.extend_from_slice(name);
+        raw_key.extend(key.encode());
+        raw_key
+    }
+
+    fn take_v0<K: Encode, V: Decode>(name: &[u8], key: &K) -> Option<V> {
+        RuntimeStorage.take(&Self::key_v0(name, key))
+    }
+
+    // a detail of version 0 was any bytes, those that are not a CID are kept as an identity
+    // multihash of raw content
+    fn content_id_v0(detail: &[u8]) -> ContentId {
+        Self::parse_content_id(detail).unwrap_or_else(|_| ContentId {
+            version: 1,
+            codec: 0x55,
+            hash_code: 0x00,
+            digest: detail.to_vec(),
+        })
+    }
+
+    fn has_queued_proposals(dao_id: DaoId) -> bool {
+        Self::access_proposals_count(dao_id) > Self::processed_access_proposals_count(dao_id)
+            || Self::unprocssed_queue_length(dao_id) > 0
+            || <PendingCredits<T>>::exists((dao_id, false))
+            || <PendingCredits<T>>::exists((dao_id, true))
+    }
+
+    /// Queue `dao_id` for on_initialize, unless it is already queued.
+    fn schedule_dao(dao_id: DaoId) {
+        if Self::is_scheduled(dao_id) {
+            return;
+        }
+        // a dao holds at most one slot, so the ring never has more than DaosCount entries
+        let length = Self::scheduled_daos_length();
+        <ScheduledDaos<T>>::insert(Self::scheduled_daos_head().wrapping_add(length), dao_id);
+        <ScheduledDaosLength<T>>::put(length + 1);
+        <IsScheduled<T>>::insert(dao_id, true);
+    }
+
+    // callers check that the ring is not empty
+    fn unschedule_next_dao() -> DaoId {
+        let head = Self::scheduled_daos_head();
+        let dao_id = <ScheduledDaos<T>>::take(head);
+        <ScheduledDaosHead<T>>::put(head.wrapping_add(1));
+        <ScheduledDaosLength<T>>::mutate(|n| *n -= 1);
+        <IsScheduled<T>>::remove(dao_id);
+        dao_id
+    }
+
+    fn process_access_proposal(dao_id: DaoId) {
+        let processed_access_proposals_count = Self::processed_access_proposals_count(dao_id);
+        if Self::access_proposals_count(dao_id) > processed_access_proposals_count{
+            // judge to process access proposal
+            let mut first_unprocessed_access_proposal = Self::access_proposals((dao_id, processed_access_proposals_count));
+            if Self::has_voting_period_expired(dao_id, first_unprocessed_access_proposal.starting_period) {
+                // process the first_unprocessed_access_proposal
+                first_unprocessed_access_proposal.processed = true;
+                first_unprocessed_access_proposal.did_pass = (first_unprocessed_access_proposal.yes_votes > first_unprocessed_access_proposal.no_votes)
+                                                    && !first_unprocessed_access_proposal.aborted;
+
+                // the payment must be covered by the free pool, the tribute of this proposal included
+                if first_unprocessed_access_proposal.payment_requested > <T::Balance as As<u64>>::sa(0) {
+                    let mut payment_available = Self::free_pool((dao_id, first_unprocessed_access_proposal.payment_asset));
+                    if first_unprocessed_access_proposal.deposit_asset == first_unprocessed_access_proposal.payment_asset {
+                        payment_available = payment_available.checked_add(&first_unprocessed_access_proposal.deposit).unwrap_or(payment_available);
+                    }
+                    first_unprocessed_access_proposal.did_pass = first_unprocessed_access_proposal.did_pass
+                                                        && payment_available >= first_unprocessed_access_proposal.payment_requested;
+                }
+
+                // a block must never panic, so a proposal that cannot be settled as voted is settled as failed
+                let settled = if Self::settle_access_proposal(dao_id, &first_unprocessed_access_proposal).is_err() {
+                    first_unprocessed_access_proposal.did_pass = false;
+                    Self::refund_access_proposal(dao_id, &first_unprocessed_access_proposal);
+                    Self::deposit_event(RawEvent::ProcessingSkipped(dao_id, false, processed_access_proposals_count));
+                    false
+                } else {
+                    Self::rate_access_proposal(dao_id, &first_unprocessed_access_proposal);
+                    true
+                };
+
+                // update access proposal
+                <AccessProposals<T>>::insert((dao_id, processed_access_proposals_count), first_unprocessed_access_proposal.clone());
+                <ProcessedAccessProposalsCount<T>>::insert(dao_id, processed_access_proposals_count + 1);
+                Self::list_remove(dao_id, ProposalList::OpenAccess, (false, processed_access_proposals_count));
+                // votes only earn rewards on a proposal settled as voted
+                let end = Self::access_voters_count((dao_id, processed_access_proposals_count));
+                Self::note_processed(dao_id, false, processed_access_proposals_count, 0, end, settled && !first_unprocessed_access_proposal.aborted);
+
+                Self::deposit_event(RawEvent::ProcessAccessProposal(dao_id, processed_access_proposals_count, first_unprocessed_access_proposal.proposer, first_unprocessed_access_proposal.applicant.clone(),
+                first_unprocessed_access_proposal.deposit, first_unprocessed_access_proposal.energies_requested, first_unprocessed_access_proposal.did_pass));
+            }
+        }
+    }
+
+    /// Move the shares and funds of a processed access proposal. Every new value is computed
+    /// before the first write, so nothing is written when this fails.
+    fn settle_access_proposal(dao_id: DaoId, access_proposal: &AccessProposal<T::AccountId, T::Balance>) -> Result {
+        if !access_proposal.did_pass {
+            Self::refund_access_proposal(dao_id, access_proposal);
+            return Ok(());
+        }
+
+        let new_total_energies_requested = Self::total_energies_requested(dao_id).checked_sub(access_proposal.energies_requested).ok_or(DaoError::Overflow)?;
+        let new_mortgage_pool = Self::mortgage_pool(dao_id).checked_sub(&access_proposal.mortgage).ok_or(DaoError::Overflow)?;
+        let deposit_key = (dao_id, access_proposal.deposit_asset);
+        let new_deposit_pool = Self::deposit_pool(deposit_key).checked_sub(&access_proposal.deposit).ok_or(DaoError::Overflow)?;
+
+        let applicant_key = (dao_id, access_proposal.applicant.clone());
+        let mut member = Self::members(applicant_key.clone());
+        member.energy = member.energy.checked_add(access_proposal.energies_requested).ok_or(DaoError::Overflow)?;
+        member.loot = member.loot.checked_add(access_proposal.loot_requested).ok_or(DaoError::Overflow)?;
+        let new_total_energies = Self::total_energies(dao_id).checked_add(access_proposal.energies_requested).ok_or(DaoError::Overflow)?;
+        let new_total_loot = Self::total_loot(dao_id).checked_add(access_proposal.loot_requested).ok_or(DaoError::Overflow)?;
+
+        // the tribute joins the free pool before the payment leaves it
+        let payment_key = (dao_id, access_proposal.payment_asset);
+        let new_tribute_free_pool = Self::free_pool(deposit_key).checked_add(&access_proposal.deposit).ok_or(DaoError::Overflow)?;
+        let payment_free_pool = if payment_key == deposit_key { new_tribute_free_pool } else { Self::free_pool(payment_key) };
+        let new_payment_free_pool = payment_free_pool.checked_sub(&access_proposal.payment_requested).ok_or(DaoError::Overflow)?;
+
+        if access_proposal.energies_requested == 0 && access_proposal.loot_requested == 0 {
+            // nothing to mint, a pure payment or whitelist proposal
+        } else if <Members<T>>::exists(applicant_key.clone()) {
+            // member already exists
+            Self::sync_active_member(dao_id, &access_proposal.applicant, member.energy);
+            <Members<T>>::insert(applicant_key, member);
+            if access_proposal.loot_requested > 0 {
+                Self::deposit_event(RawEvent::LootGranted(dao_id, access_proposal.applicant.clone(), access_proposal.loot_requested));
+            }
+        } else {
+            // the applicant is a new member, create a new record
+            Self::add_new_member(dao_id, access_proposal.applicant.clone(), access_proposal.energies_requested, access_proposal.loot_requested);
+        }
+        // mint new energies and loot
+        <TotalEnergies<T>>::insert(dao_id, new_total_energies);
+        <TotalLoot<T>>::insert(dao_id, new_total_loot);
+
+        if access_proposal.deposit > <T::Balance as As<u64>>::sa(0) {
+            // move deposit from deposit pool to free balance
+            <FreePool<T>>::insert(deposit_key, new_tribute_free_pool);
+            Self::note_treasury_asset(dao_id, access_proposal.deposit_asset);
+        }
+
+        if access_proposal.payment_requested > <T::Balance as As<u64>>::sa(0) {
+            // pay the applicant out of the free pool
+            <FreePool<T>>::insert(payment_key, new_payment_free_pool);
+            Self::deposit_asset(access_proposal.payment_asset, &access_proposal.applicant, access_proposal.payment_requested);
+        }
+
+        if let ProposalKind::Whitelist(asset_id) = access_proposal.kind {
+            <TokenWhitelist<T>>::insert((dao_id, asset_id), true);
+            Self::deposit_event(RawEvent::TokenWhitelisted(dao_id, asset_id));
+        }
+
+        <DepositPool<T>>::insert(deposit_key, new_deposit_pool);
+        <TotalEnergiesRequested<T>>::insert(dao_id, new_total_energies_requested);
+        let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&access_proposal.proposer, access_proposal.mortgage);
+        <MortgagePool<T>>::insert(dao_id, new_mortgage_pool);
+        Ok(())
+    }
+
+    /// Settle an access proposal as failed: the tribute goes back to the applicant, the mortgage
+    /// to the proposer, and the requested energies are released. This cannot fail, so it also
+    /// closes a passed proposal whose settlement would over- or underflow.
+    fn refund_access_proposal(dao_id: DaoId, access_proposal: &AccessProposal<T::AccountId, T::Balance>) {
+        let zero = <T::Balance as As<u64>>::sa(0);
+        if access_proposal.deposit > zero {
+            Self::deposit_asset(access_proposal.deposit_asset, &access_proposal.applicant, access_proposal.deposit);
+            <DepositPool<T>>::mutate((dao_id, access_proposal.deposit_asset), |pool| *pool = pool.checked_sub(&access_proposal.deposit).unwrap_or(zero));
+        }
+        <TotalEnergiesRequested<T>>::mutate(dao_id, |n| *n = n.saturating_sub(access_proposal.energies_requested));
+        let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&access_proposal.proposer, access_proposal.mortgage);
+        <MortgagePool<T>>::mutate(dao_id, |pool| *pool = pool.checked_sub(&access_proposal.mortgage).unwrap_or(zero));
+    }
+
+    fn process_project_proposal(dao_id: DaoId) {
+        let unprocessed_queue_length = Self::unprocssed_queue_length(dao_id);
+        if unprocessed_queue_length > 0 {
+            let first_unprocessed_project_proposal_index = Self::projects_process_queue((dao_id, Self::unprocssed_queue_head(dao_id)));
+            let mut first_unprocessed_project_proposal = Self::project_proposals((dao_id, first_unprocessed_project_proposal_index));
+
+            if Self::has_voting_period_expired(dao_id, first_unprocessed_project_proposal.starting_period) {
+                // process the project
+                first_unprocessed_project_proposal.processed = true;
+                first_unprocessed_project_proposal.stage_did_pass = (first_unprocessed_project_proposal.yes_votes > first_unprocessed_project_proposal.no_votes)
+                                                    && !first_unprocessed_project_proposal.aborted;
+
+                let grant_this_stage = Self::stage_grant(&first_unprocessed_project_proposal);
+
+                // a block must never panic, so a stage that cannot be settled as voted is settled as failed
+                let settled = if Self::settle_project_stage(dao_id, first_unprocessed_project_proposal_index, &first_unprocessed_project_proposal, grant_this_stage).is_err() {
+                    first_unprocessed_project_proposal.stage_did_pass = false;
+                    Self::release_stage_grant(dao_id, &first_unprocessed_project_proposal, grant_this_stage);
+                    Self::deposit_event(RawEvent::ProcessingSkipped(dao_id, true, first_unprocessed_project_proposal_index));
+                    false
+                } else {
+                    if first_unprocessed_project_proposal.stage_did_pass && first_unprocessed_project_proposal.status == ProjectStatus::Milestone3 {
+                        first_unprocessed_project_proposal.completed = true;
+                    }
+                    Self::rate_project_stage(dao_id, &first_unprocessed_project_proposal);
+                    true
+                };
+
+                // update project proposal
+                <ProjectProposals<T>>::insert((dao_id, first_unprocessed_project_proposal_index), first_unprocessed_project_proposal.clone());
+                <UnprocessedQueueHead<T>>::mutate(dao_id, |n| *n = n.saturating_add(1));
+                Self::list_remove(dao_id, ProposalList::OpenProject(first_unprocessed_project_proposal.status), (true, first_unprocessed_project_proposal_index));
+                <UnprocessedQueueLength<T>>::insert(dao_id, unprocessed_queue_length - 1);
+                // the votes of a stage follow those of the stages processed before it
+                let voters_key = (dao_id, first_unprocessed_project_proposal_index);
+                let (start, end) = (Self::project_voters_credited(voters_key), Self::project_voters_count(voters_key));
+                Self::note_processed(dao_id, true, first_unprocessed_project_proposal_index, start, end, settled && !first_unprocessed_project_proposal.aborted);
+
+                Self::deposit_event(RawEvent::ProcessProjectProposal(
+                    dao_id,
+                    first_unprocessed_project_proposal_index,
+                    first_unprocessed_project_proposal.proposer,
+                    first_unprocessed_project_proposal.applicant,
+                    first_unprocessed_project_proposal.status,
+                    first_unprocessed_project_proposal.round,
+                    first_unprocessed_project_proposal.stage_did_pass,
+                    grant_this_stage));
+            }
+        }
+    }
+
+    // a skipped proposal is not rated, its outcome is not up to its accounts. Only a proposal
+    // moving funds earns reputation, so that passing empty ones cannot be farmed.
+    fn rate_access_proposal(dao_id: DaoId, access_proposal: &AccessProposal<T::AccountId, T::Balance>) {
+        let zero = <T::Balance as As<u64>>::sa(0);
+        if access_proposal.aborted {
+            Self::adjust_reputation(dao_id, &access_proposal.applicant, reputation::ABORTED);
+        } else if access_proposal.did_pass {
+            if access_proposal.deposit > zero || access_proposal.payment_requested > zero {
+                Self::adjust_reputation(dao_id, &access_proposal.proposer, reputation::PASSED);
+            }
+        } else {
+            Self::adjust_reputation(dao_id, &access_proposal.proposer, reputation::FAILED);
+        }
+    }
+
+    fn rate_project_stage(dao_id: DaoId, project_proposal: &ProjectProposal<T::AccountId, T::Balance>) {
+        let zero = <T::Balance as As<u64>>::sa(0);
+        let requests_grant = project_proposal.milestone_1_requested > zero
+            || project_proposal.milestone_2_requested > zero
+            || project_proposal.milestone_3_requested > zero;
+        if project_proposal.completed {
+            if requests_grant {
+                Self::adjust_reputation(dao_id, &project_proposal.proposer, reputation::COMPLETED);
+                Self::adjust_reputation(dao_id, &project_proposal.applicant, reputation::COMPLETED);
+            }
+        } else if project_proposal.stage_did_pass {
+            if requests_grant {
+                Self::adjust_reputation(dao_id, &project_proposal.applicant, reputation::PASSED);
+            }
+        } else if project_proposal.aborted {
+            Self::adjust_reputation(dao_id, &project_proposal.applicant, reputation::ABORTED);
+        } else {
+            Self::adjust_reputation(dao_id, &project_proposal.applicant, reputation::FAILED);
+        }
+    }
+
+    fn adjust_reputation(dao_id: DaoId, who: &T::AccountId, change: i64) {
+        let key = (dao_id, who.clone());
+        let score = Self::reputation(key.clone()).saturating_add(change);
+        <Reputation<T>>::insert(key, score);
+        Self::deposit_event(RawEvent::ReputationChanged(dao_id, who.clone(), score));
+    }
+
+    /// The energy of `who` raised by `ReputationVoteBonus` percent per point of reputation,
+    /// by at most `reputation::MAX_VOTE_BONUS` percent.
+    pub fn vote_weight(dao_id: DaoId, who: &T::AccountId, energy: u64) -> result::Result<u64, DaoError> {
+        let score = Self::reputation((dao_id, who.clone()));
+        if score <= 0 {
+            return Ok(energy);
+        }
+        let bonus = (score as u64).saturating_mul(Self::reputation_vote_bonus());
+        let bonus = if bonus < reputation::MAX_VOTE_BONUS { bonus } else { reputation::MAX_VOTE_BONUS };
+        let extra = energy.checked_mul(bonus).ok_or(DaoError::Overflow)? / 100;
+        energy.checked_add(extra).ok_or(DaoError::Overflow)
+    }
+
+    /// Pay out or release the grant locked for a processed project stage. Like
+    /// `settle_access_proposal`, nothing is written when this fails.
+    fn settle_project_stage(dao_id: DaoId, index: u32, project_proposal: &ProjectProposal<T::AccountId, T::Balance>, grant_this_stage: T::Balance) -> Result {
+        if !project_proposal.stage_did_pass {
+            Self::release_stage_grant(dao_id, project_proposal, grant_this_stage);
+            return Ok(());
+        }
+
+        let grant_key = (dao_id, project_proposal.grant_asset);
+        let new_grant_locked_pool = Self::grant_locked_pool(grant_key).checked_sub(&grant_this_stage).ok_or(DaoError::Overflow)?;
+        let stream_periods = Self::grant_stream_periods(dao_id);
+        let new_stream_locked_pool = Self::stream_locked_pool(grant_key).checked_add(&grant_this_stage).ok_or(DaoError::Overflow)?;
+
+        if project_proposal.status == ProjectStatus::Milestone3 {
+            // the project is completed, the mortgage goes back to the proposer
+            let new_mortgage_pool = Self::mortgage_pool(dao_id).checked_sub(&project_proposal.mortgage).ok_or(DaoError::Overflow)?;
+            let _ = <balances::Module<T> as Currency<_>>::deposit_creating(&project_proposal.proposer, project_proposal.mortgage);
+            <MortgagePool<T>>::insert(dao_id, new_mortgage_pool);
+        }
+
+        if grant_this_stage > <T::Balance as As<u64>>::sa(0) {
+            if stream_periods > 0 {
+                let stage = project_proposal.status;
+                <GrantStreams<T>>::insert((dao_id, index, stage), GrantStream {
+                    recipient: project_proposal.applicant.clone(),
+                    asset: project_proposal.grant_asset,
+                    total: grant_this_stage,
+                    claimed: <T::Balance as As<u64>>::sa(0),
+                    start_period: Self::get_current_period(dao_id),
+                    periods: stream_periods,
+                });
+                <StreamLockedPool<T>>::insert(grant_key, new_stream_locked_pool);
+                Self::deposit_event(RawEvent::GrantStreamStarted(dao_id, index, stage, project_proposal.applicant.clone(), project_proposal.grant_asset, grant_this_stage, stream_periods));
+            } else {
+                Self::deposit_asset(project_proposal.grant_asset, &project_proposal.applicant, grant_this_stage);
+            }
+        }
+
+        <GrantLockedPool<T>>::insert(grant_key, new_grant_locked_pool);
+        Ok(())
+    }
+
+    /// Settle a project stage as failed: its grant goes from the locked pool back to the free
+    /// pool. Like `refund_access_proposal`, this cannot fail.
+    fn release_stage_grant(dao_id: DaoId, project_proposal: &ProjectProposal<T::AccountId, T::Balance>, grant_this_stage: T::Balance) {
+        let grant_key = (dao_id, project_proposal.grant_asset);
+        <GrantLockedPool<T>>::mutate(grant_key, |pool| *pool = pool.checked_sub(&grant_this_stage).unwrap_or_else(|| <T::Balance as As<u64>>::sa(0)));
+        <FreePool<T>>::mutate(grant_key, |pool| *pool = pool.checked_add(&grant_this_stage).unwrap_or(*pool));
+    }
+}
+
+/// The transaction payment handler of `balances`: fees are burned, and the ones paid by the
+/// current extrinsic are noted so that a vote can give some of them back.
+impl<T: Trait> OnUnbalanced<<balances::Module<T> as Currency<T::AccountId>>::NegativeImbalance> for Module<T> {
+    fn on_unbalanced(fee: <balances::Module<T> as Currency<T::AccountId>>::NegativeImbalance) {
+        let index = <system::Module<T>>::extrinsic_index();
+        let (noted_index, noted) = Self::extrinsic_fees();
+        let paid = if noted_index == index { noted.checked_add(&fee.peek()).unwrap_or(noted) } else { fee.peek() };
+        <ExtrinsicFees<T>>::put((index, paid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use runtime_io::with_externalities;
+    use primitives::{H256, Blake2Hasher};
+    use support::{impl_outer_origin, assert_ok};
+    use runtime_primitives::{
+        BuildStorage,
+        traits::{BlakeTwo256, IdentityLookup, OnInitialize},
+        testing::{Digest, DigestItem, Header}
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type Digest = Digest;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type Log = DigestItem;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = DaoModule;
+        type DustRemoval = ();
+        type TransferPayment = ();
+    }
+    impl timestamp::Trait for Test {
+        type Moment = u64;
+        type OnTimestampSet = ();
+    }
+    impl assets::Trait for Test {
+        type Event = ();
+    }
+    impl Trait for Test {
+        type Event = ();
+    }
+    type DaoModule = Module<Test>;
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Timestamp = timestamp::Module<Test>;
+    type Assets = assets::Module<Test>;
+
+    const ACCOUNTS: u64 = 6;
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+        t.extend(balances::GenesisConfig::<Test> {
+            transaction_base_fee: 0,
+            transaction_byte_fee: 0,
+            existential_deposit: 0,
+            transfer_fee: 0,
+            creation_fee: 0,
+            balances: (1..=ACCOUNTS).map(|k| (k, 1 << 40)).collect(),
+            vesting: vec![],
+        }.build_storage().unwrap().0);
+        t.extend(GenesisConfig::<Test> {
+            summon_deposit: 0,
+            max_detail_length: 64,
+            max_profile_length: 128,
+            byte_deposit: 1,
+            retention_periods: 2,
+            reap_reward: 1,
+            weight_fee: 0,
+            vote_fee_refund: 0,
+            reputation_vote_bonus: 0,
+            vote_reward_budget: 0,
+        }.build_storage().unwrap().0);
+        t.into()
+    }
+
+    // a CIDv0 of an all-zero digest
+    fn detail() -> Vec<u8> {
+        let mut raw = vec![0x12, 0x20];
+        raw.extend_from_slice(&[0u8; 32]);
+        raw
+    }
+
+    // xorshift, enough to drive reproducible call sequences without extra dependencies
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn account(&mut self) -> u64 {
+            self.below(ACCOUNTS) + 1
+        }
+
+        // mostly small values, sometimes zero or close to the limit
+        fn amount(&mut self) -> u64 {
+            match self.below(6) {
+                0 => u64::max_value() - self.below(3),
+                1 => 0,
+                _ => self.below(1000),
+            }
+        }
+    }
+
+    #[test]
+    fn random_calls_never_panic_in_block_initialization() {
+        for seed in 1..=32 {
+            with_externalities(&mut new_test_ext(), || {
+                let mut rng = Rng(seed);
+                let mut now = 1;
+                Timestamp::set_timestamp(now);
+                assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, 5, true, 0, 0, 0, vec![(1, 100, 0), (2, u64::max_value() - 100, 0)]));
+
+                for block in 1..=300 {
+                    let who = rng.account();
+                    let access_index = rng.below(DaoModule::access_proposals_count(0) as u64 + 1) as u32;
+                    let project_index = rng.below(DaoModule::project_proposals_count(0) as u64 + 1) as u32;
+                    let _ = match rng.below(11) {
+                        0 => DaoModule::donate(Origin::signed(who), 0, NATIVE_ASSET_ID, rng.amount()),
+                        1 => DaoModule::applicant_approve(Origin::signed(who), 0, rng.account(), NATIVE_ASSET_ID, rng.amount()),
+                        2 => DaoModule::submit_funding_proposal(Origin::signed(who), 0, rng.account(), NATIVE_ASSET_ID, rng.amount(),
+                            NATIVE_ASSET_ID, rng.amount(), rng.amount(), rng.amount(), detail()),
+                        3 => DaoModule::submit_project_proposal(Origin::signed(who), 0, rng.account(), NATIVE_ASSET_ID, rng.amount(),
+                            rng.amount(), rng.amount(), detail()),
+                        4 | 5 => DaoModule::submit_access_vote(Origin::signed(who), 0, access_index, rng.below(2) == 0),
+                        6 => DaoModule::submit_project_vote(Origin::signed(who), 0, project_index, rng.below(2) == 0),
+                        7 => DaoModule::forward_to_milestone(Origin::signed(who), 0, project_index),
+                        8 => DaoModule::rage_quit(Origin::signed(who), 0, rng.amount(), rng.amount()),
+                        9 => DaoModule::transfer_energy(Origin::signed(who), 0, rng.account(), rng.amount()),
+                        _ => DaoModule::abort_access(Origin::signed(who), 0, access_index),
+                    };
+
+                    now += rng.below(15);
+                    Timestamp::set_timestamp(now);
+                    <DaoModule as OnInitialize<u64>>::on_initialize(block);
+
+                    assert_eq!(DaoModule::check_invariants(), Ok(()));
+                }
+            });
+        }
+    }
+
+    const MORTGAGE: u64 = 1_000_000;
+
+    fn summon_dao() {
+        Timestamp::set_timestamp(1);
+        assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(1, 100, 0), (2, 100, 0)]));
+    }
+
+    fn set_free_balance(who: u64, value: u64) {
+        let _ = <Balances as Currency<_>>::make_free_balance_be(&who, value);
+    }
+
+    // every balance, pool, counter and allowance a dao call may touch
+    fn snapshot(dao_id: DaoId) -> Vec<u64> {
+        let mut state = vec![
+            DaoModule::mortgage_pool(dao_id),
+            DaoModule::free_pool((dao_id, NATIVE_ASSET_ID)),
+            DaoModule::deposit_pool((dao_id, NATIVE_ASSET_ID)),
+            DaoModule::grant_locked_pool((dao_id, NATIVE_ASSET_ID)),
+            DaoModule::total_energies_requested(dao_id),
+            DaoModule::access_proposals_count(dao_id) as u64,
+            DaoModule::project_proposals_count(dao_id) as u64,
+            DaoModule::unprocssed_queue_length(dao_id) as u64,
+            DaoModule::access_drafts_count(dao_id) as u64,
+            DaoModule::project_drafts_count(dao_id) as u64,
+            DaoModule::total_energies(dao_id),
+            DaoModule::members_count(dao_id) as u64,
+            DaoModule::daos_count() as u64,
+        ];
+        for who in 1..=ACCOUNTS {
+            state.push(Balances::free_balance(&who));
+            state.push(Balances::reserved_balance(&who));
+            for spender in 1..=ACCOUNTS {
+                state.push(DaoModule::allowance((dao_id, who, spender, NATIVE_ASSET_ID)));
+            }
+        }
+        state
+    }
+
+    #[test]
+    fn failed_storage_deposit_leaves_no_access_proposal_state() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500));
+            set_free_balance(1, 10);
+
+            let before = snapshot(0);
+            assert!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()).is_err());
+            assert_eq!(snapshot(0), before);
+        });
+    }
+
+    #[test]
+    fn failed_mortgage_leaves_no_access_proposal_state() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500));
+            // enough for the storage deposit but not for the mortgage
+            set_free_balance(1, MORTGAGE / 2);
+
+            let before = snapshot(0);
+            assert!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()).is_err());
+            assert_eq!(snapshot(0), before);
+        });
+    }
+
+    #[test]
+    fn failed_mortgage_leaves_no_project_proposal_state() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            set_free_balance(1, MORTGAGE / 2);
+
+            let before = snapshot(0);
+            assert!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 10, 10, 10, detail()).is_err());
+            assert_eq!(snapshot(0), before);
+        });
+    }
+
+    #[test]
+    fn failed_storage_deposit_releases_draft_tribute() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            // the tribute can be reserved, the storage deposit after it cannot
+            set_free_balance(3, 1000);
+
+            let before = snapshot(0);
+            assert!(DaoModule::submit_access_draft(Origin::signed(3), 0, NATIVE_ASSET_ID, 995, NATIVE_ASSET_ID, 0, 10, 0, detail()).is_err());
+            assert_eq!(snapshot(0), before);
+        });
+    }
+
+    #[test]
+    fn failed_sponsor_keeps_the_draft() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_access_draft(Origin::signed(3), 0, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            set_free_balance(1, MORTGAGE / 2);
+
+            let before = snapshot(0);
+            assert!(DaoModule::sponsor_access_proposal(Origin::signed(1), 0, 0).is_err());
+            assert_eq!(snapshot(0), before);
+            assert!(DaoModule::access_drafts((0, 0)).is_some());
+        });
+    }
+
+    #[test]
+    fn failed_tribute_releases_summon_deposit() {
+        with_externalities(&mut new_test_ext(), || {
+            <SummonDeposit<Test>>::put(500);
+            Timestamp::set_timestamp(1);
+            // the deposit can be reserved, the tribute after it cannot
+            set_free_balance(5, 600);
+
+            let before = snapshot(0);
+            assert!(DaoModule::summon(Origin::signed(5), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(5, 100, 200)]).is_err());
+            assert_eq!(snapshot(0), before);
+            assert!(!<Members<Test>>::exists((0, 5)));
+        });
+    }
+
+    #[test]
+    fn failed_donation_leaves_the_free_pool() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            set_free_balance(2, 10);
+
+            let before = snapshot(0);
+            assert!(DaoModule::donate(Origin::signed(2), 0, NATIVE_ASSET_ID, 100).is_err());
+            assert_eq!(snapshot(0), before);
+        });
+    }
+
+    #[test]
+    fn failed_profile_deposit_keeps_the_old_profile() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::set_profile(Origin::signed(1), 0, b"one".to_vec(), vec![], None, None));
+            let (profile, deposit) = (DaoModule::profiles((0, 1)), DaoModule::profile_deposit((0, 1)));
+            set_free_balance(1, 1);
+
+            let before = snapshot(0);
+            assert!(DaoModule::set_profile(Origin::signed(1), 0, b"a longer name".to_vec(), vec![], None, None).is_err());
+            assert_eq!(snapshot(0), before);
+            assert_eq!(DaoModule::profiles((0, 1)), profile);
+            assert_eq!(DaoModule::profile_deposit((0, 1)), deposit);
+        });
+    }
+
+    #[test]
+    fn failed_approval_keeps_the_allowance() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 50));
+            set_free_balance(3, 100);
+
+            let before = snapshot(0);
+            assert!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500).is_err());
+            assert_eq!(snapshot(0), before);
+        });
+    }
+
+    #[test]
+    fn failed_forward_keeps_the_project_stage() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(100);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            let project = DaoModule::project_proposals((0, 0));
+            assert!(project.stage_did_pass);
+
+            // the free pool cannot lock the grant of the first milestone
+            let before = snapshot(0);
+            assert_eq!(DaoModule::forward_to_milestone(Origin::signed(1), 0, 0), Err(DaoError::FreePoolInsufficient.into()));
+            assert_eq!(snapshot(0), before);
+            assert_eq!(DaoModule::project_proposals((0, 0)), project);
+            assert!(read_list(0, ProposalList::OpenProject(ProjectStatus::Milestone1)).is_empty());
+        });
+    }
+
+    #[test]
+    fn failed_storage_deposit_leaves_no_project_proposal_state() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            set_free_balance(1, 10);
+
+            let before = snapshot(0);
+            assert!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 10, 10, 10, detail()).is_err());
+            assert_eq!(snapshot(0), before);
+            assert!(read_list(0, ProposalList::ByProposer(1)).is_empty());
+        });
+    }
+
+    // an entry stored by the single dao of version 0
+    fn put_v0<K: Encode, V: Encode>(name: &[u8], key: &K, value: &V) {
+        RuntimeStorage.put(&DaoModule::key_v0(name, key), value);
+    }
+
+    fn access_v0(proposer: u64, applicant: u64, energies_requested: u64, deposit: u64, processed: bool) -> AccessProposalV0<u64, u64> {
+        AccessProposalV0 {
+            proposer: proposer,
+            applicant: applicant,
+            energies_requested: energies_requested,
+            mortgage: MORTGAGE,
+            deposit: deposit,
+            starting_period: 0,
+            yes_votes: 100,
+            no_votes: 0,
+            processed: processed,
+            did_pass: processed,
+            aborted: false,
+            detail: detail(),
+        }
+    }
+
+    fn project_v0(status: ProjectStatus, processed: bool) -> ProjectProposalV0<u64, u64> {
+        ProjectProposalV0 {
+            proposer: 1,
+            applicant: 4,
+            mortgage: MORTGAGE,
+            starting_period: 0,
+            milestone_1_requested: 100,
+            milestone_2_requested: 200,
+            milestone_3_requested: 300,
+            yes_votes: 100,
+            no_votes: 0,
+            processed: processed,
+            stage_did_pass: processed,
+            round: 0,
+            // the last milestone passed
+            aborted: status == ProjectStatus::Milestone3,
+            status: status,
+            detail: b"plain words".to_vec(),
+        }
+    }
+
+    #[test]
+    fn migration_imports_the_single_dao_of_version_0() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            // a chain of the first release: 2 joined through access proposal 0, access proposal 1
+            // and project 0 are open, project 1 passed its last milestone
+            put_v0(b"PeriodDuration", &(), &10u64);
+            put_v0(b"VotingPeriodLength", &(), &2u64);
+            put_v0(b"AbortWindow", &(), &1u64);
+            put_v0(b"ProposalMortgage", &(), &MORTGAGE);
+            put_v0(b"Summoner", &(), &1u64);
+            put_v0(b"SummoningTime", &(), &1u64);
+            put_v0(b"FreePool", &(), &10_000u64);
+            put_v0(b"MortgagePool", &(), &(2 * MORTGAGE));
+            put_v0(b"DepositPool", &(), &500u64);
+            put_v0(b"GrantLockedPool", &(), &100u64);
+            put_v0(b"TotalEnergies", &(), &150u64);
+            put_v0(b"TotalEnergiesRequested", &(), &10u64);
+            put_v0(b"MembersCount", &(), &2u32);
+            put_v0(b"MembersArray", &0u32, &1u64);
+            put_v0(b"MembersArray", &1u32, &2u64);
+            put_v0(b"Members", &1u64, &MemberV0 { energy: 100, highest_index_yes_vote: 0 });
+            put_v0(b"Members", &2u64, &MemberV0 { energy: 50, highest_index_yes_vote: 1 });
+            put_v0(b"AccessProposalsCount", &(), &2u32);
+            put_v0(b"ProcessedAccessProposalsCount", &(), &1u32);
+            put_v0(b"AccessProposals", &0u32, &access_v0(1, 2, 50, 0, true));
+            put_v0(b"AccessProposals", &1u32, &access_v0(2, 3, 10, 500, false));
+            put_v0(b"ProjectProposalsCount", &(), &2u32);
+            put_v0(b"ProjectProposals", &0u32, &project_v0(ProjectStatus::Milestone1, false));
+            put_v0(b"ProjectProposals", &1u32, &project_v0(ProjectStatus::Milestone3, true));
+            put_v0(b"UnprocessedQueueLength", &(), &1u32);
+            put_v0(b"ProjectsProcessQueue", &0u32, &0u32);
+            put_v0(b"VotesForAccess", &(0u32, 1u64), &true);
+            put_v0(b"VotesForAccess", &(1u32, 2u64), &true);
+            put_v0(b"VotesForProject", &(0u32, 1u64, ProjectStatus::Milestone1, 0u64), &true);
+            <StorageVersion<Test>>::put(0);
+
+            assert_eq!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 10, 10, 10, detail()),
+                Err(DaoError::MigrationInProgress.into()));
+            assert_eq!(DaoModule::donate(Origin::signed(1), 0, NATIVE_ASSET_ID, 10), Err(DaoError::MigrationInProgress.into()));
+            assert_eq!(DaoModule::summon(Origin::signed(3), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(3, 100, 0)]),
+                Err(DaoError::MigrationInProgress.into()));
+
+            for block in 1..5 {
+                <DaoModule as OnInitialize<u64>>::on_initialize(block);
+            }
+            assert_eq!(DaoModule::storage_version(), CURRENT_STORAGE_VERSION);
+            assert_eq!(DaoModule::daos_count(), 1);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            assert_eq!(DaoModule::summoner(0), Some(1));
+            assert_eq!(DaoModule::period_duration(0), 10);
+            assert_eq!(DaoModule::proposal_mortgage(0), MORTGAGE);
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 10_000);
+            assert_eq!(DaoModule::mortgage_pool(0), 2 * MORTGAGE);
+            assert_eq!(DaoModule::members((0, 2)), Member { energy: 50, loot: 0, highest_index_yes_vote: 1 });
+            assert_eq!(DaoModule::active_members_count(0), 2);
+
+            let access_proposal = DaoModule::access_proposals((0, 1));
+            assert_eq!((access_proposal.applicant, access_proposal.deposit_asset, access_proposal.deposit), (3, NATIVE_ASSET_ID, 500));
+            assert_eq!(access_proposal.detail, DaoModule::parse_content_id(&detail()).unwrap());
+            assert!(DaoModule::project_proposals((0, 1)).completed);
+            assert!(!DaoModule::project_proposals((0, 1)).aborted);
+            // a detail that is not a CID is kept as raw content
+            assert_eq!(DaoModule::project_proposals((0, 0)).detail,
+                ContentId { version: 1, codec: 0x55, hash_code: 0x00, digest: b"plain words".to_vec() });
+            assert_eq!(DaoModule::projects_process_queue((0, 0)), 0);
+
+            assert_eq!(DaoModule::votes_for_access((0, 1, 2)), Some(true));
+            assert_eq!(DaoModule::access_voter((0, 1, 0)), 2);
+            assert_eq!(DaoModule::project_voter((0, 0, 0)), (1, ProjectStatus::Milestone1, 0));
+            assert_eq!(DaoModule::proposals_voted((0, 1)), 2);
+            assert!(!RuntimeStorage.exists(&DaoModule::key_v0(b"FreePool", &())));
+            assert!(!RuntimeStorage.exists(&DaoModule::key_v0(b"Members", &2u64)));
+            assert!(!RuntimeStorage.exists(&DaoModule::key_v0(b"VotesForAccess", &(1u32, 2u64))));
+
+            // the open proposals carry on in the keyed layout
+            assert!(DaoModule::is_scheduled(0));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 1, true));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(5);
+            assert!(DaoModule::is_member(0, &3));
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn new_chain_starts_at_current_storage_version() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(DaoModule::storage_version(), CURRENT_STORAGE_VERSION);
+        });
+    }
+
+    // a CIDv1 with the given header and a digest of `length` bytes
+    fn cid_v1(header: &[u8], length: usize) -> Vec<u8> {
+        let mut raw = vec![1];
+        raw.extend_from_slice(header);
+        raw.extend(vec![0u8; length]);
+        raw
+    }
+
+    #[test]
+    fn parse_content_id_accepts_cid_v0_and_v1() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(DaoModule::parse_content_id(&detail()), Ok(ContentId { version: 0, codec: 0x70, hash_code: 0x12, digest: vec![0; 32] }));
+            // dag-cbor, whose codec takes a two byte varint
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x71, 0x12, 0x20], 32)), Ok(ContentId { version: 1, codec: 0x71, hash_code: 0x12, digest: vec![0; 32] }));
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x85, 0x01, 0x12, 0x20], 32)).map(|cid| cid.codec), Ok(0x85));
+        });
+    }
+
+    #[test]
+    fn parse_content_id_rejects_bad_input() {
+        with_externalities(&mut new_test_ext(), || {
+            let invalid = Err(DaoError::InvalidDetail.into());
+            assert_eq!(DaoModule::parse_content_id(&[]), invalid);
+
+            // wrong version byte
+            let mut raw = cid_v1(&[0x70, 0x12, 0x20], 32);
+            raw[0] = 2;
+            assert_eq!(DaoModule::parse_content_id(&raw), invalid);
+            let mut raw = detail();
+            raw[0] = 0x13;
+            assert_eq!(DaoModule::parse_content_id(&raw), invalid);
+
+            // a varint whose continuation bit runs past the input, or past 9 bytes
+            assert_eq!(DaoModule::parse_content_id(&[1, 0x80]), invalid);
+            assert_eq!(DaoModule::parse_content_id(&[1, 0x70, 0x12, 0xa0]), invalid);
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0xff; 9], 1)), invalid);
+
+            // the digest does not match its declared length
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x70, 0x12, 0x20], 31)), invalid);
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x70, 0x12, 0x20], 33)), invalid);
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x70, 0x12, 0x00], 0)), invalid);
+
+            // longer than MaxDetailLength, even if well formed
+            assert_eq!(DaoModule::max_detail_length(), 64);
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x70, 0x12, 0x3d], 61)), Err(DaoError::DetailTooLong.into()));
+            assert_eq!(DaoModule::parse_content_id(&cid_v1(&[0x70, 0x12, 0x3c], 60)).map(|cid| cid.digest.len()), Ok(60));
+        });
+    }
+
+    #[test]
+    fn summon_refuses_a_founder_listed_twice() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            assert_eq!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(1, 100, 0), (2, 100, 0), (1, 50, 0)]),
+                Err(DaoError::DuplicateFounder.into()));
+            assert_eq!(DaoModule::daos_count(), 0);
+            assert!(!DaoModule::is_member(0, &1));
+        });
+    }
+
+    #[test]
+    fn summon_takes_the_tribute_of_other_founders_from_their_allowance() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            // founders approve the summoner before the dao exists
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(2), 0, 1, NATIVE_ASSET_ID, 300));
+            assert_eq!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(1, 100, 0), (2, 100, 301)]),
+                Err(DaoError::FounderAllowanceTooLow.into()));
+
+            let before = Balances::free_balance(2);
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(1, 100, 0), (2, 100, 200)]));
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 200);
+            assert_eq!(DaoModule::allowance((0, 2, 1, NATIVE_ASSET_ID)), 100);
+            assert_eq!(Balances::reserved_balance(2), 100);
+            assert_eq!(Balances::free_balance(2), before);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn summon_reserves_a_deposit_and_blocks_visit_only_busy_daos() {
+        with_externalities(&mut new_test_ext(), || {
+            <SummonDeposit<Test>>::put(500);
+            summon_dao();
+            assert_eq!(Balances::reserved_balance(&1), 500);
+            assert_eq!(DaoModule::dao_deposit(0), 500);
+
+            set_free_balance(5, 100);
+            assert!(DaoModule::summon(Origin::signed(5), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(5, 100, 0)]).is_err());
+            assert_eq!(DaoModule::daos_count(), 1);
+
+            let busy = MAX_DAOS_PER_BLOCK + 1;
+            for dao_id in 1..=busy {
+                assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(1, 100, 0)]));
+                assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), dao_id, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            }
+            assert_eq!(DaoModule::scheduled_daos_length(), busy);
+            assert!(!DaoModule::is_scheduled(0));
+
+            Timestamp::set_timestamp(100);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert!((1..busy).all(|dao_id| DaoModule::project_proposals((dao_id, 0)).processed));
+            assert!(!DaoModule::project_proposals((busy, 0)).processed);
+            assert_eq!(DaoModule::scheduled_daos_length(), 1);
+
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert!(DaoModule::project_proposals((busy, 0)).processed);
+            assert_eq!(DaoModule::scheduled_daos_length(), 0);
+        });
+    }
+
+    // what the balances module does with the fee of an extrinsic
+    fn pay_transaction_fee(who: u64, fee: u64) {
+        let imbalance = <Balances as Currency<_>>::withdraw(&who, fee, WithdrawReason::TransactionPayment, ExistenceRequirement::KeepAlive).unwrap();
+        <DaoModule as OnUnbalanced<_>>::on_unbalanced(imbalance);
+    }
+
+    #[test]
+    fn vote_fee_refund_comes_out_of_the_fees_paid() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            <VoteFeeRefund<Test>>::put(10);
+
+            // members vote without the weight fee, so only the transaction fee is paid
+            System::set_extrinsic_index(1);
+            let (free, issuance) = (Balances::free_balance(&2), Balances::total_issuance());
+            pay_transaction_fee(2, 50);
+            assert_eq!(DaoModule::extrinsic_fees(), (Some(1), 50));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(2), 0, 0, true));
+            assert_eq!(Balances::free_balance(&2), free - 40);
+            assert_eq!(Balances::total_issuance(), issuance - 40);
+            assert_eq!(DaoModule::extrinsic_fees(), (Some(1), 40));
+
+            // the refund is capped by the fees of this extrinsic
+            <VoteFeeRefund<Test>>::put(1000);
+            System::set_extrinsic_index(2);
+            let (free, issuance) = (Balances::free_balance(&1), Balances::total_issuance());
+            pay_transaction_fee(1, 5);
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            assert_eq!(Balances::free_balance(&1), free);
+            assert_eq!(Balances::total_issuance(), issuance);
+            assert_eq!(DaoModule::extrinsic_fees(), (Some(2), 0));
+        });
+    }
+
+    #[test]
+    fn vote_fee_refund_needs_fees_of_the_same_extrinsic() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            <VoteFeeRefund<Test>>::put(10);
+
+            System::set_extrinsic_index(1);
+            pay_transaction_fee(2, 50);
+            System::set_extrinsic_index(2);
+            let (free, issuance) = (Balances::free_balance(&2), Balances::total_issuance());
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(2), 0, 0, true));
+            assert_eq!(Balances::free_balance(&2), free);
+            assert_eq!(Balances::total_issuance(), issuance);
+            assert_eq!(DaoModule::extrinsic_fees(), (Some(1), 50));
+        });
+    }
+
+        fn pool_validity() -> TransactionValidity {
+        TransactionValidity::Valid { priority: 1, requires: vec![], provides: vec![vec![0]], longevity: 64 }
+    }
+
+    #[test]
+    fn vote_transactions_are_checked_against_the_proposal() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+
+            // voting on the first project is open for two periods
+            assert_eq!(DaoModule::validate_vote_transaction(pool_validity(), &2, 0, true, 0), TransactionValidity::Valid {
+                priority: 1 + VOTE_PRIORITY / 2,
+                requires: vec![],
+                provides: vec![vec![0], (&b"dao_vote"[..], 0u32, true, 0u32, 2u64).encode()],
+                longevity: 64,
+            });
+
+            // the second project is voted on in the next period
+            <ProjectProposals<Test>>::mutate((0, 1), |project| project.starting_period = 1);
+            assert_eq!(DaoModule::validate_vote_transaction(pool_validity(), &2, 0, true, 1),
+                TransactionValidity::Unknown(ApplyError::Future as i8));
+
+            assert_eq!(DaoModule::validate_vote_transaction(pool_validity(), &5, 0, true, 0),
+                TransactionValidity::Invalid(DaoError::NotMember.code()));
+            assert_eq!(DaoModule::validate_vote_transaction(pool_validity(), &2, 0, true, 2),
+                TransactionValidity::Invalid(DaoError::InvalidProjectProposalIndex.code()));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(2), 0, 0, true));
+            assert_eq!(DaoModule::validate_vote_transaction(pool_validity(), &2, 0, true, 0),
+                TransactionValidity::Invalid(DaoError::AlreadyVoted.code()));
+
+            // a transaction the executive rejected is left as it is
+            assert_eq!(DaoModule::validate_vote_transaction(TransactionValidity::Invalid(0), &1, 0, true, 0), TransactionValidity::Invalid(0));
+        });
+    }
+
+    #[test]
+    fn dao_error_codes_are_unique() {
+        for (i, e) in DaoError::ALL.iter().enumerate() {
+            assert!(e.code() > 0);
+            assert!(DaoError::ALL[..i].iter().all(|other| other.code() != e.code()));
+        }
+    }
+
+    #[test]
+    fn dao_error_messages_map_back_to_their_error() {
+        for e in DaoError::ALL.iter() {
+            assert_eq!(DaoError::from_message(e.message()), Some(*e));
+        }
+    }
+
+    #[test]
+    fn unsettleable_access_proposal_gives_back_what_it_holds() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, vec![(1, 100, 0), (2, u64::max_value() - 100, 0)]));
+            <VoteRewardBudget<Test>>::put(100);
+            let (applicant, proposer) = (Balances::free_balance(&3), Balances::free_balance(&1));
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500));
+
+            // minting the energies would overflow TotalEnergies
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 0, 200, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 0, true));
+            Timestamp::set_timestamp(100);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            let access_proposal = DaoModule::access_proposals((0, 0));
+            assert!(access_proposal.processed && !access_proposal.did_pass);
+            assert!(!DaoModule::is_member(0, &3));
+            assert_eq!(Balances::free_balance(&3), applicant);
+            assert_eq!(Balances::free_balance(&1) + Balances::reserved_balance(&1), proposer);
+            assert_eq!(DaoModule::deposit_pool((0, NATIVE_ASSET_ID)), 0);
+            assert_eq!(DaoModule::mortgage_pool(0), 0);
+            assert_eq!(DaoModule::total_energies_requested(0), 0);
+            // a vote on a proposal that was not settled as voted earns nothing
+            assert!(DaoModule::vote_reward_periods((0, 2)).is_empty());
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn invariants_hold_through_a_proposal_lifecycle() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::donate(Origin::signed(2), 0, NATIVE_ASSET_ID, 1000));
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 100, 10, 5, detail()));
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            for block in 1..=10 {
+                Timestamp::set_timestamp(block * 10);
+                <DaoModule as OnInitialize<u64>>::on_initialize(block);
+                assert_eq!(DaoModule::check_invariants(), Ok(()));
+            }
+            assert!(DaoModule::is_member(0, &3));
+
+            assert_ok!(DaoModule::forward_to_milestone(Origin::signed(1), 0, 0));
+            assert_eq!(DaoModule::grant_locked_pool((0, NATIVE_ASSET_ID)), 100);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    // asset 1, issued by 3 and accepted by dao 0
+    fn whitelisted_asset() -> AssetId {
+        assert_ok!(Assets::issue(Origin::signed(3), 1000));
+        <TokenWhitelist<Test>>::insert((0, 1), true);
+        1
+    }
+
+    #[test]
+    fn rage_quit_redeems_every_treasury_asset_pro_rata() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            let asset_id = whitelisted_asset();
+            assert_ok!(Assets::issue(Origin::signed(3), 1000));
+            assert_eq!(DaoModule::donate(Origin::signed(3), 0, 2, 100), Err(DaoError::AssetNotWhitelisted.into()));
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, asset_id, 600));
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, NATIVE_ASSET_ID, 1000));
+            assert_eq!(Assets::free_balance((asset_id, 3)), 400);
+            assert_eq!(DaoModule::free_pool((0, asset_id)), 600);
+            assert_eq!(DaoModule::treasury_assets(0), vec![NATIVE_ASSET_ID, asset_id]);
+
+            // shares can only leave once a proposal has been processed
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            // a quarter of the shares takes a quarter of each pool
+            let native_before = Balances::free_balance(1);
+            assert_ok!(DaoModule::rage_quit(Origin::signed(1), 0, 50, 0));
+            assert_eq!(Balances::free_balance(1) - native_before, 250);
+            assert_eq!(Assets::free_balance((asset_id, 1)), 150);
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 750);
+            assert_eq!(DaoModule::free_pool((0, asset_id)), 450);
+            assert_eq!(Assets::total_supply(asset_id), 1000);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn passed_whitelist_proposal_accepts_the_asset() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(Assets::issue(Origin::signed(3), 1000));
+            assert_eq!(DaoModule::submit_whitelist_proposal(Origin::signed(1), 0, 2, detail()), Err(DaoError::AssetNotFound.into()));
+            assert_eq!(DaoModule::submit_whitelist_proposal(Origin::signed(1), 0, NATIVE_ASSET_ID, detail()),
+                Err(DaoError::AssetAlreadyWhitelisted.into()));
+            assert_eq!(DaoModule::submit_whitelist_proposal(Origin::signed(3), 0, 1, detail()), Err(DaoError::NotMember.into()));
+
+            assert_ok!(DaoModule::submit_whitelist_proposal(Origin::signed(1), 0, 1, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_eq!(DaoModule::donate(Origin::signed(3), 0, 1, 100), Err(DaoError::AssetNotWhitelisted.into()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            assert!(DaoModule::token_whitelist((0, 1)));
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, 1, 100));
+            assert_eq!(DaoModule::submit_whitelist_proposal(Origin::signed(1), 0, 1, detail()), Err(DaoError::AssetAlreadyWhitelisted.into()));
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn failed_whitelist_proposal_leaves_the_asset_out() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(Assets::issue(Origin::signed(3), 1000));
+            assert_ok!(DaoModule::submit_whitelist_proposal(Origin::signed(1), 0, 1, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 0, false));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            assert!(!DaoModule::token_whitelist((0, 1)));
+            assert!(!DaoModule::access_proposals((0, 0)).did_pass);
+            assert_eq!(DaoModule::donate(Origin::signed(3), 0, 1, 100), Err(DaoError::AssetNotWhitelisted.into()));
+        });
+    }
+
+    #[test]
+    fn tribute_in_another_asset_joins_its_free_pool() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            let asset_id = whitelisted_asset();
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, asset_id, 500));
+            assert_eq!(Assets::reserved_balance((asset_id, 3)), 500);
+
+            assert_eq!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, 2, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()),
+                Err(DaoError::TributeAssetNotWhitelisted.into()));
+            assert_eq!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, asset_id, 501, NATIVE_ASSET_ID, 0, 10, 0, detail()),
+                Err(DaoError::ApplicantAllowanceTooLow.into()));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, asset_id, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_eq!(Assets::reserved_balance((asset_id, 3)), 0);
+            assert_eq!(DaoModule::deposit_pool((0, asset_id)), 500);
+            assert_eq!(DaoModule::deposit_pool((0, NATIVE_ASSET_ID)), 0);
+
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            assert!(DaoModule::is_member(0, &3));
+            assert_eq!(DaoModule::deposit_pool((0, asset_id)), 0);
+            assert_eq!(DaoModule::free_pool((0, asset_id)), 500);
+            assert_eq!(DaoModule::treasury_assets(0), vec![NATIVE_ASSET_ID, asset_id]);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn tribute_in_another_asset_goes_back_when_the_proposal_fails() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            let asset_id = whitelisted_asset();
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, asset_id, 500));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, asset_id, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, false));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            assert!(!DaoModule::is_member(0, &3));
+            assert_eq!(Assets::free_balance((asset_id, 3)), 1000);
+            assert_eq!(DaoModule::deposit_pool((0, asset_id)), 0);
+            assert_eq!(DaoModule::free_pool((0, asset_id)), 0);
+            assert_eq!(DaoModule::treasury_assets(0), vec![NATIVE_ASSET_ID]);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn rage_quit_of_loot_pays_out_in_proportion() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::donate(Origin::signed(4), 0, NATIVE_ASSET_ID, 1000));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 200, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert_eq!(DaoModule::total_loot(0), 200);
+
+            // 3 holds half of the 400 shares without being a member
+            assert!(!DaoModule::is_member(0, &3));
+            assert_eq!(DaoModule::rage_quit(Origin::signed(3), 0, 0, 201), Err(DaoError::LootTooLow.into()));
+            assert_eq!(DaoModule::rage_quit(Origin::signed(3), 0, 1, 0), Err(DaoError::EnergyTooLow.into()));
+
+            let before = Balances::free_balance(3);
+            assert_ok!(DaoModule::rage_quit(Origin::signed(3), 0, 0, 100));
+            assert_eq!(Balances::free_balance(3) - before, 250);
+            assert_ok!(DaoModule::rage_quit(Origin::signed(3), 0, 0, 100));
+            assert_eq!(Balances::free_balance(3) - before, 500);
+
+            assert_eq!(DaoModule::members((0, 3)).loot, 0);
+            assert_eq!(DaoModule::total_loot(0), 0);
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 500);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn transfer_energy_is_refused_to_the_wrong_accounts_and_while_voting() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            // energy can only move once a proposal has been processed
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(1), 0, 1, 10), Err(DaoError::TransferToSelf.into()));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(1), 0, 3, 10), Err(DaoError::ReceiverNotMember.into()));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(3), 0, 1, 10), Err(DaoError::NotMember.into()));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(1), 0, 2, 0), Err(DaoError::NothingToTransfer.into()));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(1), 0, 2, 101), Err(DaoError::EnergyTooLow.into()));
+
+            // a voter keeps its energy until the vote closes, a yes voter until the proposal is processed
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 1, true));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 1, false));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(1), 0, 2, 10), Err(DaoError::YesVoteNotProcessed.into()));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(2), 0, 1, 10), Err(DaoError::VotesNotClosed.into()));
+
+            Timestamp::set_timestamp(60);
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert_ok!(DaoModule::transfer_energy(Origin::signed(2), 0, 1, 10));
+            assert_eq!(DaoModule::members((0, 1)).energy, 110);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            // a dao may keep its energy where it was granted
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, false, 0, 0, 0, vec![(1, 100, 0), (2, 100, 0)]));
+            assert_eq!(DaoModule::transfer_energy(Origin::signed(1), 1, 2, 10), Err(DaoError::EnergyNotTransferable.into()));
+        });
+    }
+
+    #[test]
+    fn transferring_all_energy_forgets_the_decayed_energy() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            <DecayedEnergy<Test>>::insert((0, 1), 7);
+            <DecayedPeriods<Test>>::insert((0, 1), 2);
+
+            assert_ok!(DaoModule::transfer_energy(Origin::signed(1), 0, 2, 60));
+            assert_eq!(DaoModule::decayed_energy((0, 1)), 7);
+            assert_ok!(DaoModule::transfer_energy(Origin::signed(1), 0, 2, 40));
+            assert_eq!(DaoModule::decayed_energy((0, 1)), 0);
+            assert_eq!(DaoModule::decayed_periods((0, 1)), 0);
+            assert!(!DaoModule::is_member(0, &1));
+        });
+    }
+
+    #[test]
+    fn decrease_allowance_gives_back_the_reserved_funds() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500));
+            assert_eq!(Balances::reserved_balance(3), 500);
+
+            assert_ok!(DaoModule::decrease_allowance(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 200));
+            assert_eq!(DaoModule::allowance((0, 3, 1, NATIVE_ASSET_ID)), 300);
+            assert_eq!(Balances::reserved_balance(3), 300);
+
+            assert_eq!(DaoModule::decrease_allowance(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 301), Err(DaoError::AllowanceTooLow.into()));
+            assert_eq!(DaoModule::decrease_allowance(Origin::signed(3), 0, 2, NATIVE_ASSET_ID, 1), Err(DaoError::AllowanceTooLow.into()));
+            assert_eq!(DaoModule::allowance((0, 3, 1, NATIVE_ASSET_ID)), 300);
+            assert_eq!(Balances::reserved_balance(3), 300);
+        });
+    }
+
+    #[test]
+    fn revoke_clears_the_allowance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(DaoModule::revoke(Origin::signed(3), 0, 1, NATIVE_ASSET_ID), Err(DaoError::NoAllowance.into()));
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 500));
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 2, NATIVE_ASSET_ID, 100));
+
+            assert_ok!(DaoModule::revoke(Origin::signed(3), 0, 1, NATIVE_ASSET_ID));
+            assert!(!<Allowance<Test>>::exists((0, 3, 1, NATIVE_ASSET_ID)));
+            assert_eq!(Balances::reserved_balance(3), 100);
+            // the allowance of another spender is kept
+            assert_eq!(DaoModule::allowance((0, 3, 2, NATIVE_ASSET_ID)), 100);
+            assert_eq!(DaoModule::revoke(Origin::signed(3), 0, 1, NATIVE_ASSET_ID), Err(DaoError::NoAllowance.into()));
+        });
+    }
+
+    #[test]
+    fn sponsored_access_draft_becomes_a_proposal() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_access_draft(Origin::signed(3), 0, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            let draft_deposit = DaoModule::access_drafts((0, 0)).unwrap().storage_deposit;
+            assert_eq!(Balances::reserved_balance(3), 500 + draft_deposit);
+
+            assert_eq!(DaoModule::sponsor_access_proposal(Origin::signed(4), 0, 0), Err(DaoError::NotMember.into()));
+            assert_ok!(DaoModule::sponsor_access_proposal(Origin::signed(1), 0, 0));
+            assert_eq!(DaoModule::access_drafts((0, 0)), None);
+            assert_eq!(DaoModule::sponsor_access_proposal(Origin::signed(1), 0, 0), Err(DaoError::DraftNotFound.into()));
+
+            // the tribute moved to the dao, and the sponsor holds the storage deposit now
+            let access_proposal = DaoModule::access_proposals((0, 0));
+            assert_eq!((access_proposal.proposer, access_proposal.applicant, access_proposal.deposit), (1, 3, 500));
+            assert_eq!(Balances::reserved_balance(3), 0);
+            assert_eq!(Balances::reserved_balance(1), access_proposal.storage_deposit);
+            assert_eq!(DaoModule::deposit_pool((0, NATIVE_ASSET_ID)), 500);
+
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert_eq!(DaoModule::members((0, 3)).energy, 10);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn sponsored_project_draft_becomes_a_proposal() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_project_draft(Origin::signed(3), 0, NATIVE_ASSET_ID, 10, 20, 30, detail()));
+            assert_ok!(DaoModule::sponsor_project_proposal(Origin::signed(2), 0, 0));
+            assert_eq!(DaoModule::project_drafts((0, 0)), None);
+            assert_eq!(Balances::reserved_balance(3), 0);
+
+            let project_proposal = DaoModule::project_proposals((0, 0));
+            assert_eq!((project_proposal.proposer, project_proposal.applicant, project_proposal.milestone_3_requested), (2, 3, 30));
+            assert_eq!(DaoModule::mortgage_pool(0), MORTGAGE);
+            assert_eq!(DaoModule::sponsor_project_proposal(Origin::signed(2), 0, 0), Err(DaoError::DraftNotFound.into()));
+        });
+    }
+
+    #[test]
+    fn cancelled_drafts_give_back_what_they_reserved() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            let before = Balances::free_balance(3);
+            assert_ok!(DaoModule::submit_access_draft(Origin::signed(3), 0, NATIVE_ASSET_ID, 500, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_project_draft(Origin::signed(3), 0, NATIVE_ASSET_ID, 10, 20, 30, detail()));
+            assert!(Balances::reserved_balance(3) > 500);
+
+            assert_eq!(DaoModule::cancel_access_draft(Origin::signed(1), 0, 0), Err(DaoError::NotDraftApplicant.into()));
+            assert_eq!(DaoModule::cancel_project_draft(Origin::signed(1), 0, 0), Err(DaoError::NotDraftApplicant.into()));
+            assert_ok!(DaoModule::cancel_access_draft(Origin::signed(3), 0, 0));
+            assert_ok!(DaoModule::cancel_project_draft(Origin::signed(3), 0, 0));
+
+            assert_eq!(DaoModule::access_drafts((0, 0)), None);
+            assert_eq!(DaoModule::project_drafts((0, 0)), None);
+            assert_eq!(Balances::reserved_balance(3), 0);
+            assert_eq!(Balances::free_balance(3), before);
+            assert_eq!(DaoModule::cancel_access_draft(Origin::signed(3), 0, 0), Err(DaoError::DraftNotFound.into()));
+            assert_eq!(DaoModule::sponsor_project_proposal(Origin::signed(1), 0, 0), Err(DaoError::DraftNotFound.into()));
+        });
+    }
+
+    #[test]
+    fn check_invariants_reports_the_broken_pool() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+
+            <MortgagePool<Test>>::insert(0, MORTGAGE - 1);
+            assert_eq!(DaoModule::check_invariants(), Err((0, Invariant::MortgagePool)));
+
+            <MortgagePool<Test>>::insert(0, MORTGAGE);
+            <GrantLockedPool<Test>>::insert((0, NATIVE_ASSET_ID), 1);
+            assert_eq!(DaoModule::check_invariants(), Err((0, Invariant::GrantLockedPool(NATIVE_ASSET_ID))));
+        });
+    }
+
+    // every entry of a list, read three at a time
+    fn read_list(dao_id: DaoId, list: ProposalList<u64>) -> Vec<(bool, u32)> {
+        let mut entries = Vec::new();
+        loop {
+            let page = DaoModule::proposal_list(dao_id, list.clone(), entries.len() as u32, 3);
+            if page.is_empty() {
+                return entries;
+            }
+            entries.extend(page);
+        }
+    }
+
+    #[test]
+    fn indices_follow_proposals_and_votes() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::donate(Origin::signed(2), 0, NATIVE_ASSET_ID, 1000));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(2), 0, 3, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+
+            assert_eq!(read_list(0, ProposalList::OpenAccess), vec![(false, 0)]);
+            assert_eq!(read_list(0, ProposalList::OpenProject(ProjectStatus::Initialization)), vec![(true, 0)]);
+            assert_eq!(read_list(0, ProposalList::ByProposer(1)), vec![(false, 0)]);
+            assert_eq!(read_list(0, ProposalList::ByProposer(2)), vec![(true, 0)]);
+            assert_eq!(read_list(0, ProposalList::ByApplicant(3)), vec![(false, 0), (true, 0)]);
+
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            assert_eq!(read_list(0, ProposalList::VotedBy(1)), vec![(false, 0), (true, 0)]);
+
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert!(read_list(0, ProposalList::OpenAccess).is_empty());
+            assert!(read_list(0, ProposalList::OpenProject(ProjectStatus::Initialization)).is_empty());
+
+            assert_ok!(DaoModule::forward_to_milestone(Origin::signed(1), 0, 0));
+            assert_eq!(read_list(0, ProposalList::OpenProject(ProjectStatus::Milestone1)), vec![(true, 0)]);
+
+            // reaping drops the proposal from every index
+            Timestamp::set_timestamp(100);
+            assert_ok!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 0));
+            assert!(read_list(0, ProposalList::ByProposer(1)).is_empty());
+            assert_eq!(read_list(0, ProposalList::ByApplicant(3)), vec![(true, 0)]);
+            assert_eq!(read_list(0, ProposalList::VotedBy(1)), vec![(true, 0)]);
+        });
+    }
+
+    #[test]
+    fn reaping_pays_the_reaper_per_voter_and_archives_the_proposal() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 0, true));
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            <WeightFee<Test>>::put(1);
+
+            // kept for the retention periods after its voting ended
+            assert_eq!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 0), Err(DaoError::RetentionNotPassed.into()));
+            assert_eq!(DaoModule::access_proposals_archive((0, 0)), None);
+
+            Timestamp::set_timestamp(40);
+            let reaper = Balances::free_balance(&4);
+            assert_ok!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 0));
+            assert_eq!(Balances::free_balance(&4), reaper + 1 - (weight::REAP + 2 * weight::REAP_PER_VOTER));
+            assert!(!<AccessProposals<Test>>::exists((0, 0)));
+            assert_eq!(DaoModule::access_voters_count((0, 0)), 0);
+            assert_eq!(DaoModule::votes_for_access((0, 0, 2)), None);
+            assert_eq!(DaoModule::access_proposals_archive((0, 0)), Some(ProposalRecord { did_pass: true, yes_votes: 200, no_votes: 0 }));
+            assert_eq!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 0), Err(DaoError::InvalidAccessProposalIndex.into()));
+        });
+    }
+
+    #[test]
+    fn reaping_gives_the_storage_deposit_back_to_the_proposer() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            let storage_deposit = DaoModule::access_proposals((0, 0)).storage_deposit;
+            assert!(storage_deposit > 1);
+            assert_eq!(Balances::reserved_balance(1), storage_deposit);
+            Timestamp::set_timestamp(40);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            // the reaper takes the reward out of the deposit, the proposer gets the rest
+            let proposer = Balances::free_balance(1);
+            assert_ok!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 0));
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(1), proposer + storage_deposit - 1);
+        });
+    }
+
+    #[test]
+    fn removed_list_entry_is_filled_by_the_last_one() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            for _ in 0..5 {
+                assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            }
+            assert_eq!(DaoModule::proposal_list(0, ProposalList::ByProposer(1), 3, 10), vec![(false, 3), (false, 4)]);
+            assert_eq!(DaoModule::proposal_list(0, ProposalList::ByProposer(1), 5, 10), vec![]);
+
+            Timestamp::set_timestamp(40);
+            for block in 1..=5 {
+                <DaoModule as OnInitialize<u64>>::on_initialize(block);
+            }
+            assert!(read_list(0, ProposalList::OpenAccess).is_empty());
+
+            assert_ok!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 1));
+            assert_eq!(read_list(0, ProposalList::ByProposer(1)), vec![(false, 0), (false, 4), (false, 2), (false, 3)]);
+            assert_eq!(DaoModule::proposal_list_position((0, ProposalList::ByProposer(1), (false, 4))), Some(1));
+            assert_eq!(DaoModule::proposal_list_position((0, ProposalList::ByProposer(1), (false, 1))), None);
+
+            assert_ok!(DaoModule::reap_proposal(Origin::signed(4), 0, false, 3));
+            assert_eq!(read_list(0, ProposalList::ByApplicant(3)), vec![(false, 0), (false, 4), (false, 2)]);
+        });
+    }
+
+    fn page_accounts(dao_id: DaoId, after: Option<u64>, limit: u32) -> Vec<u64> {
+        DaoModule::active_members(dao_id, after, limit).into_iter().map(|m| m.account).collect()
+    }
+
+    #[test]
+    fn active_members_page_across_removals() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            let founders = (1..=6).map(|who| (who, 10, 0)).collect();
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, founders));
+            assert_eq!(DaoModule::active_members_count(0), 6);
+            // shares can only leave once a proposal has been processed
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            let first_page = page_accounts(0, None, 2);
+            assert_eq!(first_page, vec![1, 2]);
+
+            // the last member of the page and the next one leave
+            assert_ok!(DaoModule::rage_quit(Origin::signed(2), 0, 10, 0));
+            assert_ok!(DaoModule::transfer_energy(Origin::signed(3), 0, 6, 10));
+            assert_eq!(page_accounts(0, Some(2), 2), vec![4, 5]);
+            assert_eq!(page_accounts(0, Some(5), 2), vec![6]);
+            assert_eq!(page_accounts(0, None, 10), vec![1, 4, 5, 6]);
+            assert_eq!(DaoModule::active_members_count(0), 4);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            let member = &DaoModule::active_members(0, Some(5), 1)[0];
+            assert_eq!((member.energy, member.joined_period, member.proposals_voted), (20, 0, 0));
+        });
+    }
+
+    #[test]
+    fn returning_member_is_linked_at_the_tail() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert_ok!(DaoModule::rage_quit(Origin::signed(1), 0, 100, 0));
+            assert_eq!(page_accounts(0, None, 10), vec![2]);
+
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(2), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 5, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 1, true));
+            Timestamp::set_timestamp(60);
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+
+            assert_eq!(page_accounts(0, None, 10), vec![2, 1]);
+            assert_eq!(DaoModule::active_members(0, None, 1)[0].proposals_voted, 1);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn paging_from_a_returning_member_ends_at_the_tail() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            let founders = (1..=4).map(|who| (who, 10, 0)).collect();
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, founders));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert_eq!(page_accounts(0, None, 2), vec![1, 2]);
+
+            // the last member of the page leaves and joins again before the next page is read
+            assert_ok!(DaoModule::rage_quit(Origin::signed(2), 0, 10, 0));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 2, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 5, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 1, true));
+            Timestamp::set_timestamp(60);
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+
+            // 3 and 4 are skipped, which the count of active members gives away
+            assert_eq!(page_accounts(0, Some(2), 2), Vec::<u64>::new());
+            assert_eq!(DaoModule::active_members_count(0), 4);
+            assert_eq!(page_accounts(0, None, 10), vec![1, 3, 4, 2]);
+        });
+    }
+
+    #[test]
+    fn profile_deposit_follows_its_size() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            let reserved = || Balances::reserved_balance(&1);
+
+            assert_eq!(DaoModule::set_profile(Origin::signed(3), 0, b"carol".to_vec(), vec![], None, None),
+                Err(DaoError::NotShareholder.into()));
+            assert_eq!(DaoModule::set_profile(Origin::signed(1), 0, vec![0; 128], vec![], None, None),
+                Err(DaoError::ProfileTooLong.into()));
+
+            assert_ok!(DaoModule::set_profile(Origin::signed(1), 0, b"alice".to_vec(), b"alice@example.org".to_vec(), Some(detail()), Some([7; 32])));
+            let profile = DaoModule::profiles((0, 1)).unwrap();
+            assert_eq!(reserved(), profile.encode().len() as u64);
+            assert_eq!(DaoModule::profile_deposit((0, 1)), reserved());
+
+            assert_ok!(DaoModule::set_profile(Origin::signed(1), 0, b"alice".to_vec(), vec![], None, None));
+            assert_eq!(reserved(), DaoModule::profiles((0, 1)).unwrap().encode().len() as u64);
+
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(2), 0, 1, NATIVE_ASSET_ID, 10, 10, 10, detail()));
+            let (proposer, applicant) = DaoModule::proposal_profiles(0, true, 0).unwrap();
+            assert_eq!((proposer, applicant.map(|p| p.display_name)), (None, Some(b"alice".to_vec())));
+
+            assert_ok!(DaoModule::clear_profile(Origin::signed(1), 0));
+            assert_eq!(reserved(), 0);
+            assert_eq!(DaoModule::clear_profile(Origin::signed(1), 0), Err(DaoError::NoProfile.into()));
+        });
+    }
+
+    #[test]
+    fn empty_proposals_earn_no_reputation() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            // neither a tribute nor a payment, and a project without grants
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(30);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert!(DaoModule::access_proposals((0, 0)).did_pass);
+            assert!(DaoModule::project_proposals((0, 0)).stage_did_pass);
+            assert_eq!(DaoModule::reputation((0, 1)), 0);
+
+            // a failure still counts
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 1, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            Timestamp::set_timestamp(60);
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert_eq!(DaoModule::reputation((0, 1)), reputation::FAILED);
+        });
+    }
+
+    #[test]
+    fn reputation_follows_outcomes_and_weights_votes() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            // one passes, one fails and one is aborted by its applicant
+            assert_ok!(DaoModule::applicant_approve(Origin::signed(3), 0, 1, NATIVE_ASSET_ID, 100));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 100, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(2), 0, 4, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(2), 0, 5, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_ok!(DaoModule::abort_access(Origin::signed(5), 0, 2));
+
+            for block in 1..=3 {
+                Timestamp::set_timestamp(100);
+                <DaoModule as OnInitialize<u64>>::on_initialize(block);
+            }
+            assert_eq!(DaoModule::reputation((0, 1)), reputation::PASSED);
+            assert_eq!(DaoModule::reputation((0, 2)), reputation::FAILED);
+            assert_eq!(DaoModule::reputation((0, 5)), reputation::ABORTED);
+
+            // no bonus unless configured, then capped
+            assert_eq!(DaoModule::vote_weight(0, &1, 100), Ok(100));
+            <ReputationVoteBonus<Test>>::put(10);
+            assert_eq!(DaoModule::vote_weight(0, &1, 100), Ok(120));
+            assert_eq!(DaoModule::vote_weight(0, &2, 100), Ok(100));
+            <ReputationVoteBonus<Test>>::put(1000);
+            assert_eq!(DaoModule::vote_weight(0, &1, 100), Ok(200));
+
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(2), 0, 6, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 3, true));
+            assert_eq!(DaoModule::access_proposals((0, 3)).yes_votes, 200);
+        });
+    }
+
+    #[test]
+    fn vote_rewards_share_the_period_budget() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            <VoteRewardBudget<Test>>::put(100);
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, NATIVE_ASSET_ID, 1000));
+
+            // both proposals are settled in period 2, where 1 voted twice and 2 once
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(2), 0, 4, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 1, true));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 0, false));
+            // votes on an aborted proposal earn nothing
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(2), 0, 5, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 2, true));
+            assert_ok!(DaoModule::abort_access(Origin::signed(5), 0, 2));
+            assert_eq!(DaoModule::period_vote_points((0, 0)), 0);
+
+            Timestamp::set_timestamp(20);
+            for block in 1..4 {
+                <DaoModule as OnInitialize<u64>>::on_initialize(block);
+            }
+            assert_eq!(DaoModule::period_vote_points((0, 2)), 3);
+            assert_eq!(DaoModule::vote_points((0, 2, 1)), 2);
+            assert_eq!(DaoModule::vote_points((0, 2, 2)), 1);
+            // a period is shared once it is over
+            assert_eq!(DaoModule::claim_vote_rewards(Origin::signed(1), 0), Err(DaoError::NoVoteRewards.into()));
+
+            Timestamp::set_timestamp(30);
+            let (before_1, before_2) = (Balances::free_balance(&1), Balances::free_balance(&2));
+            assert_ok!(DaoModule::claim_vote_rewards(Origin::signed(1), 0));
+            assert_eq!(Balances::free_balance(&1) - before_1, 66);
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 900);
+            assert_eq!(DaoModule::vote_reward_pool(0), 34);
+            assert_eq!(DaoModule::period_vote_rewards((0, 2)), Some((34, 1)));
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            // the last claim takes what rounding left
+            assert_ok!(DaoModule::claim_vote_rewards(Origin::signed(2), 0));
+            assert_eq!(Balances::free_balance(&2) - before_2, 34);
+            assert_eq!(DaoModule::vote_reward_pool(0), 0);
+            assert_eq!(DaoModule::period_vote_rewards((0, 2)), None);
+            assert!(DaoModule::open_reward_periods(0).is_empty());
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            assert_eq!(DaoModule::claim_vote_rewards(Origin::signed(1), 0), Err(DaoError::NoVoteRewards.into()));
+        });
+    }
+
+    #[test]
+    fn vote_rewards_are_capped_by_the_free_pool() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            <VoteRewardBudget<Test>>::put(100);
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, NATIVE_ASSET_ID, 40));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+
+            // the first proposal is settled in period 2, the second waits for period 4
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 1, true));
+            Timestamp::set_timestamp(40);
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+
+            let before = Balances::free_balance(&1);
+            assert_ok!(DaoModule::claim_vote_rewards(Origin::signed(1), 0));
+            assert_eq!(Balances::free_balance(&1) - before, 40);
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 0);
+            assert_eq!(DaoModule::vote_reward_periods((0, 1)), vec![4]);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            // without a budget no points are written
+            <VoteRewardBudget<Test>>::put(0);
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 5, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 2, true));
+            Timestamp::set_timestamp(60);
+            <DaoModule as OnInitialize<u64>>::on_initialize(3);
+            assert!(DaoModule::access_proposals((0, 2)).processed);
+            assert_eq!(DaoModule::period_vote_points((0, 6)), 0);
+            assert_eq!(DaoModule::vote_reward_periods((0, 1)), vec![4]);
+        });
+    }
+
+    #[test]
+    fn voters_of_a_processed_proposal_are_credited_a_batch_per_block() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            let voters = MAX_CREDITED_VOTERS as u64 + 1;
+            let founders = (1..=voters).map(|who| (who, 100, 0)).collect();
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 0, founders));
+            <VoteRewardBudget<Test>>::put(100);
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 100, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 10, 0, detail()));
+            for who in 1..=voters {
+                assert_ok!(DaoModule::submit_access_vote(Origin::signed(who), 0, 0, true));
+            }
+
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert!(DaoModule::access_proposals((0, 0)).processed);
+            assert_eq!(DaoModule::period_vote_points((0, 2)), MAX_CREDITED_VOTERS);
+            assert_eq!(DaoModule::active_since((0, voters)), 0);
+            assert!(DaoModule::pending_credit((0, false)).is_some());
+            assert_eq!(DaoModule::reap_proposal(Origin::signed(1), 0, false, 0), Err(DaoError::ProposalNotSettled.into()));
+
+            // the dao stays scheduled until its last voter is credited
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert_eq!(DaoModule::period_vote_points((0, 2)), voters as u32);
+            assert_eq!(DaoModule::active_since((0, voters)), 1);
+            assert_eq!(DaoModule::pending_credit((0, false)), None);
+            assert_eq!(DaoModule::scheduled_daos_length(), 0);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn vote_rewards_of_an_unknown_dao_are_refused() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(20);
+            assert_eq!(DaoModule::get_current_period(0), 0);
+            assert_eq!(DaoModule::claim_vote_rewards(Origin::signed(1), 0), Err(DaoError::DaoNotFound.into()));
+        });
+    }
+
+    #[test]
+    fn inactive_energy_decays_and_comes_back_on_vote() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            assert_eq!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 1, 101, 0, vec![(1, 100, 0), (2, 100, 0)]),
+                Err(DaoError::InvalidDecayPercent.into()));
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 1, 10, 0, vec![(1, 100, 0), (2, 100, 0)]));
+
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+
+            // 2 missed the processed proposal and has not voted for two periods
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert_eq!(DaoModule::members((0, 1)).energy, 100);
+            assert_eq!(DaoModule::members((0, 2)).energy, 81);
+            assert_eq!(DaoModule::decayed_energy((0, 2)), 19);
+            assert_eq!(DaoModule::decayed_periods((0, 2)), 2);
+            assert_eq!(DaoModule::total_energies(0), 181);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            // a period decays once
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 3, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert_eq!(DaoModule::members((0, 2)).energy, 81);
+
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(2), 0, 1, true));
+            assert_eq!(DaoModule::members((0, 2)).energy, 100);
+            assert_eq!(DaoModule::decayed_energy((0, 2)), 0);
+            assert_eq!(DaoModule::decayed_periods((0, 2)), 0);
+            assert_eq!(DaoModule::last_vote_period((0, 2)), 2);
+            assert_eq!(DaoModule::total_energies(0), 200);
+            assert_eq!(DaoModule::access_proposals((0, 1)).yes_votes, 100);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn energy_decay_visits_a_bounded_number_of_members_per_block() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            let founders = (1..=MAX_DECAY_MEMBERS as u64 + 1).map(|who| (who, 100, 0)).collect();
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 1, 10, 0, founders));
+            assert_ok!(DaoModule::submit_funding_proposal(Origin::signed(1), 0, 100, NATIVE_ASSET_ID, 0, NATIVE_ASSET_ID, 0, 0, 0, detail()));
+            assert_ok!(DaoModule::submit_access_vote(Origin::signed(1), 0, 0, true));
+
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            let last = MAX_DECAY_MEMBERS as u64 + 1;
+            assert_eq!(DaoModule::members((0, 2)).energy, 81);
+            assert_eq!(DaoModule::members((0, last)).energy, 100);
+            assert_eq!(DaoModule::decay_cursor(0), Some(last));
+
+            // the sweep keeps the dao scheduled until it is done
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert_eq!(DaoModule::members((0, last)).energy, 81);
+            assert_eq!(DaoModule::decay_cursor(0), None);
+            assert_eq!(DaoModule::scheduled_daos_length(), 0);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn streamed_grant_vests_and_returns_on_cancel() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(1);
+            assert_ok!(DaoModule::summon(Origin::signed(1), 10, 2, 1, MORTGAGE, true, 0, 0, 4, vec![(1, 100, 0), (2, 100, 0)]));
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, NATIVE_ASSET_ID, 1000));
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+
+            // milestone 1 passes in period 4
+            assert_ok!(DaoModule::forward_to_milestone(Origin::signed(1), 0, 0));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, true));
+            Timestamp::set_timestamp(40);
+            <DaoModule as OnInitialize<u64>>::on_initialize(2);
+            assert_eq!(DaoModule::stream_locked_pool((0, NATIVE_ASSET_ID)), 100);
+            assert_eq!(DaoModule::grant_locked_pool((0, NATIVE_ASSET_ID)), 0);
+            assert_eq!(DaoModule::claim_stream(Origin::signed(4), 0, 0, ProjectStatus::Milestone1), Err(DaoError::NothingVested.into()));
+            assert_eq!(DaoModule::claim_stream(Origin::signed(1), 0, 0, ProjectStatus::Milestone1), Err(DaoError::NotProposalApplicant.into()));
+
+            Timestamp::set_timestamp(60);
+            let before = Balances::free_balance(&4);
+            assert_ok!(DaoModule::claim_stream(Origin::signed(4), 0, 0, ProjectStatus::Milestone1));
+            assert_eq!(Balances::free_balance(&4) - before, 50);
+            assert_eq!(DaoModule::stream_locked_pool((0, NATIVE_ASSET_ID)), 50);
+            assert_eq!(DaoModule::project_streams(0, 0)[0].2, 0);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            // the stage passed, so only the applicant may stop the project
+            Timestamp::set_timestamp(70);
+            assert_eq!(DaoModule::cancel_project(Origin::signed(2), 0, 0), Err(DaoError::ProjectNotCancellable.into()));
+            let score = DaoModule::reputation((0, 4));
+            assert_ok!(DaoModule::cancel_project(Origin::signed(4), 0, 0));
+            assert_eq!(DaoModule::reputation((0, 4)), score + reputation::ABORTED);
+            assert_eq!(Balances::free_balance(&4) - before, 75);
+            assert_eq!(DaoModule::free_pool((0, NATIVE_ASSET_ID)), 925);
+            assert_eq!(DaoModule::stream_locked_pool((0, NATIVE_ASSET_ID)), 0);
+            assert_eq!(DaoModule::grant_streams((0, 0, ProjectStatus::Milestone1)), None);
+            assert_eq!(DaoModule::mortgage_pool(0), 0);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+
+            assert_eq!(DaoModule::forward_to_milestone(Origin::signed(1), 0, 0), Err(DaoError::ProjectProposalAborted.into()));
+        });
+    }
+
+    #[test]
+    fn members_dropping_a_failed_project_leave_the_applicant_reputation() {
+        with_externalities(&mut new_test_ext(), || {
+            summon_dao();
+            assert_ok!(DaoModule::donate(Origin::signed(3), 0, NATIVE_ASSET_ID, 1000));
+            assert_ok!(DaoModule::submit_project_proposal(Origin::signed(1), 0, 4, NATIVE_ASSET_ID, 100, 100, 100, detail()));
+            assert_ok!(DaoModule::submit_project_vote(Origin::signed(1), 0, 0, false));
+            Timestamp::set_timestamp(20);
+            <DaoModule as OnInitialize<u64>>::on_initialize(1);
+            assert!(!DaoModule::project_proposals((0, 0)).stage_did_pass);
+
+            let score = DaoModule::reputation((0, 4));
+            assert_ok!(DaoModule::cancel_project(Origin::signed(2), 0, 0));
+            assert!(DaoModule::project_proposals((0, 0)).aborted);
+            assert_eq!(DaoModule::reputation((0, 4)), score);
+            assert_eq!(DaoModule::check_invariants(), Ok(()));
+        });
+    }
+
+    #[test]
+    fn streams_of_an_unknown_dao_are_empty() {
+        with_externalities(&mut new_test_ext(), || {
+            Timestamp::set_timestamp(20);
+            assert!(DaoModule::project_streams(0, 0).is_empty());
+            summon_dao();
+            assert!(DaoModule::project_streams(1, 0).is_empty());
+        });
+    }
+}
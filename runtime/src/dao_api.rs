@@ -0,0 +1,40 @@
+//! Runtime API of the dao module, callable by clients through `state_call`.
+//!
+//! The node has no dao RPC methods of its own: the service of the Substrate version this node
+//! is built on offers no way to register them. `proposal_detail`, `check_invariants`,
+//! `active_members` and the other queries are reached with `state_call("DaoApi_<method>", args)`,
+//! where `args` are the SCALE-encoded parameters and the result is SCALE-encoded as well.
+
+use client::decl_runtime_apis;
+use parity_codec::Codec;
+use rstd::vec::Vec;
+use crate::dao::{DaoId, ContentId, DaoError, Invariant, ProjectStatus, ProposalList, MemberInfo, Profile, GrantStream};
+
+decl_runtime_apis! {
+	/// Read-only queries over the dao state.
+	pub trait DaoApi<AccountId, Balance> where AccountId: Codec, Balance: Codec {
+		/// The content identifier of an access (`is_project == false`) or project proposal.
+		fn proposal_detail(dao_id: DaoId, is_project: bool, index: u32) -> Option<ContentId>;
+		/// Every dao error with the message its dispatch result carries.
+		fn errors() -> Vec<(DaoError, Vec<u8>)>;
+		/// The first dao and invariant found broken, if any. Not reliable while a storage migration runs.
+		fn check_invariants() -> Result<(), (DaoId, Invariant)>;
+		/// Up to `limit` entries of a proposal list from position `start`, at most
+		/// `MAX_PROPOSALS_PAGE`, with the length of the list.
+		fn proposal_list(dao_id: DaoId, list: ProposalList<AccountId>, start: u32, limit: u32) -> (Vec<(bool, u32)>, u32);
+		/// Up to `limit` members with energy following `after`, at most `MAX_MEMBERS_PAGE`.
+		/// The last account of a page is the `after` of the next one, unless that member left and
+		/// came back since: it is then last, and the walk starts over from `None`.
+		fn active_members(dao_id: DaoId, after: Option<AccountId>, limit: u32) -> Vec<MemberInfo<AccountId>>;
+		/// The number of members with energy, which a full walk of `active_members` returns.
+		fn active_members_count(dao_id: DaoId) -> u32;
+		/// The profile a member set, if any.
+		fn profile(dao_id: DaoId, who: AccountId) -> Option<Profile>;
+		/// The reputation of an account, 0 if none of its proposals was rated.
+		fn reputation(dao_id: DaoId, who: AccountId) -> i64;
+		/// The open grant streams of a project by stage, with the amount claimable now.
+		fn project_streams(dao_id: DaoId, index: u32) -> Vec<(ProjectStatus, GrantStream<AccountId, Balance>, Balance)>;
+		/// The profiles of the proposer and the applicant of a proposal.
+		fn proposal_profiles(dao_id: DaoId, is_project: bool, index: u32) -> Option<(Option<Profile>, Option<Profile>)>;
+	}
+}
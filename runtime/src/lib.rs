@@ -13,7 +13,7 @@ use rstd::prelude::*;
 use primitives::bytes;
 use primitives::{ed25519, sr25519, OpaqueMetadata};
 use runtime_primitives::{
-	ApplyResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
+	ApplyResult, ApplyError, transaction_validity::TransactionValidity, generic, create_runtime_str,
 	traits::{self, NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify}
 };
 use client::{
@@ -55,7 +55,12 @@ pub type BlockNumber = u64;
 /// Index of an account's extrinsic in the chain.
 pub type Nonce = u64;
 
+/// Balance of an account.
+pub type Balance = u128;
+
+mod assets;
 mod dao;
+pub mod dao_api;
 
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
@@ -167,7 +172,7 @@ impl timestamp::Trait for Runtime {
 
 impl balances::Trait for Runtime {
 	/// The type for recording an account's balance.
-	type Balance = u128;
+	type Balance = Balance;
 	/// What to do if an account's free balance gets zeroed.
 	type OnFreeBalanceZero = ();
 	/// What to do if a new account is created.
@@ -175,7 +180,8 @@ impl balances::Trait for Runtime {
 	/// The uniquitous event type.
 	type Event = Event;
 
-	type TransactionPayment = ();
+	/// Fees are burned by the dao module, which gives members part of their vote fees back.
+	type TransactionPayment = DaoModule;
 	type DustRemoval = ();
 	type TransferPayment = ();
 }
@@ -186,6 +192,10 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+impl assets::Trait for Runtime {
+	type Event = Event;
+}
+
 impl dao::Trait for Runtime {
 	type Event = Event;
 }
@@ -203,6 +213,7 @@ construct_runtime!(
 		Indices: indices,
 		Balances: balances,
 		Sudo: sudo,
+		Assets: assets::{Module, Call, Storage, Event<T>},
 		DaoModule: dao::{Module, Call, Storage, Event<T>, Config<T>},
 	}
 );
@@ -274,7 +285,23 @@ impl_runtime_apis! {
 
 	impl runtime_api::TaggedTransactionQueue<Block> for Runtime {
 		fn validate_transaction(tx: <Block as BlockT>::Extrinsic) -> TransactionValidity {
-			Executive::validate_transaction(tx)
+			let vote = match (&tx.signature, &tx.function) {
+				(Some((address, _, _, _)), Call::DaoModule(dao::Call::submit_access_vote(dao_id, index, _))) =>
+					Some((address.clone(), *dao_id, false, *index)),
+				(Some((address, _, _, _)), Call::DaoModule(dao::Call::submit_project_vote(dao_id, index, _))) =>
+					Some((address.clone(), *dao_id, true, *index)),
+				_ => None,
+			};
+
+			match (Executive::validate_transaction(tx), vote) {
+				(validity @ TransactionValidity::Valid { .. }, Some((address, dao_id, is_project, index))) => {
+					match Indices::lookup(address) {
+						Ok(who) => DaoModule::validate_vote_transaction(validity, &who, dao_id, is_project, index),
+						Err(_) => TransactionValidity::Invalid(ApplyError::BadSignature as i8),
+					}
+				},
+				(validity, _) => validity,
+			}
 		}
 	}
 
@@ -295,4 +322,47 @@ impl_runtime_apis! {
 			Consensus::authorities()
 		}
 	}
+
+	impl dao_api::DaoApi<Block, AccountId, Balance> for Runtime {
+		fn proposal_detail(dao_id: dao::DaoId, is_project: bool, index: u32) -> Option<dao::ContentId> {
+			DaoModule::proposal_detail(dao_id, is_project, index)
+		}
+
+		fn errors() -> Vec<(dao::DaoError, Vec<u8>)> {
+			dao::DaoError::ALL.iter().map(|e| (*e, e.message().as_bytes().to_vec())).collect()
+		}
+
+		fn check_invariants() -> Result<(), (dao::DaoId, dao::Invariant)> {
+			DaoModule::check_invariants()
+		}
+
+		fn proposal_list(dao_id: dao::DaoId, list: dao::ProposalList<AccountId>, start: u32, limit: u32) -> (Vec<(bool, u32)>, u32) {
+			let length = DaoModule::proposal_list_length((dao_id, list.clone()));
+			(DaoModule::proposal_list(dao_id, list, start, limit), length)
+		}
+
+		fn active_members(dao_id: dao::DaoId, after: Option<AccountId>, limit: u32) -> Vec<dao::MemberInfo<AccountId>> {
+			DaoModule::active_members(dao_id, after, limit)
+		}
+
+		fn active_members_count(dao_id: dao::DaoId) -> u32 {
+			DaoModule::active_members_count(dao_id)
+		}
+
+		fn profile(dao_id: dao::DaoId, who: AccountId) -> Option<dao::Profile> {
+			DaoModule::profiles((dao_id, who))
+		}
+
+		fn reputation(dao_id: dao::DaoId, who: AccountId) -> i64 {
+			DaoModule::reputation((dao_id, who))
+		}
+
+		fn project_streams(dao_id: dao::DaoId, index: u32) -> Vec<(dao::ProjectStatus, dao::GrantStream<AccountId, Balance>, Balance)> {
+			DaoModule::project_streams(dao_id, index)
+		}
+
+		fn proposal_profiles(dao_id: dao::DaoId, is_project: bool, index: u32) -> Option<(Option<dao::Profile>, Option<dao::Profile>)> {
+			DaoModule::proposal_profiles(dao_id, is_project, index)
+		}
+	}
 }
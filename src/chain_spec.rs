@@ -116,10 +116,16 @@ fn testnet_genesis(initial_authorities: Vec<AuthorityId>, endowed_accounts: Vec<
 			key: root_key,
 		}),
 		dao: Some(DaoModuleConfig {
-			period_duration: 180,
-			voting_period_length: 3,
-			abort_window: 1,
-			proposal_mortgage: 1000000000000000,
+			summon_deposit: 1000000000000,
+			max_detail_length: 64,
+			max_profile_length: 256,
+			byte_deposit: 10000000000,
+			retention_periods: 30,
+			reap_reward: 100000000000,
+			weight_fee: 1000000000,
+			vote_fee_refund: 10000000000,
+			reputation_vote_bonus: 0,
+			vote_reward_budget: 100000000000,
 		}),
 	}
 }